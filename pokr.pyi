@@ -2,10 +2,105 @@
 # ruff: noqa: E501, F401
 
 import builtins
+import enum
+import numpy
+import numpy.typing
 import typing
 
+class AgentCountMismatchError(GameError):
+    r"""
+    Agents given do not match the seats one to one
+    """
+    ...
+
+class BoardOverflowError(CardError):
+    r"""
+    Card added to a full board
+    """
+    ...
+
+class CardError(PokrError):
+    r"""
+    Error of the cards, decks and boards
+    """
+    ...
+
+class EmptyDeckError(CardError):
+    r"""
+    Card drawn from an empty deck
+    """
+    ...
+
+class GameError(PokrError):
+    r"""
+    Error of the game engine
+    """
+    ...
+
+class GameOverError(GameError):
+    r"""
+    Fewer than two seats have chips left
+    """
+    ...
+
+class InvalidActionError(GameError):
+    r"""
+    Action is not legal for the seat to act
+    """
+    ...
+
+class InvalidBytesError(GameError):
+    r"""
+    Bytes that do not describe a game
+    """
+    ...
+
+class InvalidCardError(CardError):
+    r"""
+    Text that does not describe cards
+    """
+    ...
+
+class InvalidPokerHandCardCountError(CardError):
+    r"""
+    Poker hand without five cards
+    """
+    ...
+
+class InvalidRoundCardCountError(GameError):
+    r"""
+    Board holds a card count no round has
+    """
+    ...
+
+class InvalidSeatChangeError(GameError):
+    r"""
+    Player cannot join, leave or buy chips at the seat
+    """
+    ...
+
+class InvalidSettingsError(GameError):
+    r"""
+    Settings cannot be played with
+    """
+    ...
+
+class InvalidSetupError(GameError):
+    r"""
+    Hand setup cannot be dealt
+    """
+    ...
+
+class PokrError(builtins.Exception):
+    r"""
+    Base class of the errors raised by pokr
+    """
+    ...
+
 @typing.final
 class PyAction:
+    def __eq__(self, other: builtins.object) -> builtins.bool: ...
+    def __hash__(self) -> builtins.int: ...
     @staticmethod
     def new_fold() -> PyAction: ...
     @staticmethod
@@ -14,14 +109,452 @@ class PyAction:
     def new_check() -> PyAction: ...
     @staticmethod
     def new_call() -> PyAction: ...
+    def __repr__(self) -> builtins.str: ...
+
+@typing.final
+class PyAgentIter:
+    r"""
+    Iterator over the agents of a `PyPokerEnv`, see `PyPokerEnv.agent_iter`
+    """
+    def __iter__(self) -> PyAgentIter: ...
+    def __next__(self) -> typing.Optional[builtins.str]: ...
+
+@typing.final
+class PyBettingStructure:
+    def __eq__(self, other: builtins.object) -> builtins.bool: ...
+    def __hash__(self) -> builtins.int: ...
+    @staticmethod
+    def new_no_limit() -> PyBettingStructure: ...
+    @staticmethod
+    def new_pot_limit() -> PyBettingStructure:
+        r"""
+        Raises up to the pot after calling
+        """
+    @staticmethod
+    def new_fixed_limit(small_bet: builtins.int, big_bet: builtins.int, max_bets: builtins.int = 4) -> PyBettingStructure:
+        r"""
+        Small bet on the preflop and the flop, big bet on the turn and the river, and at most
+        `max_bets` bets and raises per street, the big blind counting as the first one preflop
+        """
+    def __repr__(self) -> builtins.str: ...
+
+@typing.final
+class PyBoard:
+    r"""
+    Community cards, in the order they were dealt
+    """
+    def __eq__(self, other: builtins.object) -> builtins.bool: ...
+    def __hash__(self) -> builtins.int: ...
+    def __new__(cls, text: builtins.str = '') -> PyBoard: ...
+    def with_card(self, card: PyCard) -> PyBoard:
+        r"""
+        Board with one more card, raises `BoardOverflowError` past five cards
+        """
+    def cards(self) -> builtins.list[PyCard]: ...
+    def best_poker_hand(self, hand: PyPlayerHand) -> PyPokerHand:
+        r"""
+        Best five card hand the hole cards make with the board, which needs three cards or more
+        """
+    def __len__(self) -> builtins.int: ...
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
+@typing.final
+class PyCard:
+    r"""
+    Card built from its text, such as `As` or `Td`
+    """
+    @property
+    def value(self) -> builtins.int:
+        r"""
+        2 to 14 for an ace
+        """
+    @property
+    def suit(self) -> builtins.str:
+        r"""
+        One of `s`, `h`, `d` and `c`
+        """
+    @property
+    def index(self) -> builtins.int: ...
+    def __eq__(self, other: builtins.object) -> builtins.bool: ...
+    def __lt__(self, other: builtins.object) -> builtins.bool: ...
+    def __le__(self, other: builtins.object) -> builtins.bool: ...
+    def __gt__(self, other: builtins.object) -> builtins.bool: ...
+    def __ge__(self, other: builtins.object) -> builtins.bool: ...
+    def __hash__(self) -> builtins.int: ...
+    def __new__(cls, text: builtins.str) -> PyCard: ...
+    @staticmethod
+    def from_index(index: builtins.int) -> PyCard:
+        r"""
+        Card of index `0..52`, see `index`
+        """
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
 
 @typing.final
 class PyGame:
     def __new__(cls, settings: PySettings) -> PyGame: ...
-    def play_turn(self, action: PyAction) -> None: ...
+    def play_turn(self, action: PyAction) -> None:
+        r"""
+        Raises `InvalidActionError` when the action is not legal, `GameOverError` once the game
+        is over
+        """
+    def current_seat(self) -> builtins.int: ...
+    def observation(self, seat: builtins.int) -> PyObservation:
+        r"""
+        What `seat` can see of the game
+        """
+    def hand_count(self) -> builtins.int:
+        r"""
+        Number of hands played to the end
+        """
+    def over(self) -> builtins.bool: ...
+    def format_chips(self, amount: builtins.int) -> builtins.str:
+        r"""
+        Amount of chips written in the denomination of the game, such as `$0.02`
+        """
+    def icm_deltas(self, payouts: typing.Sequence[builtins.float]) -> typing.Optional[builtins.list[builtins.float]]:
+        r"""
+        Change of the prize equity of every seat over the last hand, `payouts` holding the prize
+        of every place from the first, or `None` before the first hand ends
+        """
+    def to_bytes(self) -> bytes:
+        r"""
+        Whole game as bytes, its generator included, see `from_bytes`
+        """
+    @staticmethod
+    def from_bytes(bytes: bytes) -> PyGame:
+        r"""
+        Game written by `to_bytes`, raises `InvalidBytesError` for anything else
+        """
+    def __reduce__(self) -> tuple[typing.Any, tuple[bytes]]:
+        r"""
+        Pickles the game as its bytes, so that an unpickled game deals the same cards
+        """
+    def __copy__(self) -> PyGame: ...
+    def __deepcopy__(self, _memo: typing.Any) -> PyGame:
+        r"""
+        Same as `__copy__`, a game holding no Python object
+        """
+
+@typing.final
+class PyObservation:
+    r"""
+    What one seat can see of the game
+    """
+    @property
+    def seat(self) -> builtins.int:
+        r"""
+        Seat the game is observed from
+        """
+    @property
+    def hand(self) -> typing.Optional[PyPlayerHand]: ...
+    @property
+    def board(self) -> builtins.list[PyCard]: ...
+    @property
     def current_seat(self) -> builtins.int: ...
+    @property
+    def sb_seat(self) -> builtins.int: ...
+    @property
+    def round(self) -> PyRound: ...
+    @property
+    def stacks(self) -> builtins.list[builtins.int]: ...
+    @property
+    def bets(self) -> builtins.list[builtins.int]:
+        r"""
+        Bets of the current round
+        """
+    @property
+    def folded(self) -> builtins.list[builtins.bool]: ...
+    @property
+    def dead(self) -> builtins.list[builtins.bool]:
+        r"""
+        Seats out of chips before the hand started
+        """
+    @property
+    def starting_stacks(self) -> builtins.list[builtins.int]:
+        r"""
+        Stacks at the start of the hand, before the blinds
+        """
+    @property
+    def pot(self) -> builtins.int:
+        r"""
+        Chips collected from the previous rounds
+        """
+    @property
+    def total_pot(self) -> builtins.int:
+        r"""
+        Chips in the middle, including the bets of the current round
+        """
+    @property
+    def small_blind(self) -> builtins.int: ...
+    @property
+    def big_blind(self) -> builtins.int: ...
+    @property
+    def call_amount(self) -> builtins.int:
+        r"""
+        Chips the seat to act needs to call, zero when it can check
+        """
+    @property
+    def raise_range(self) -> typing.Optional[tuple[builtins.int, builtins.int]]:
+        r"""
+        Smallest and largest amount a raise can add on top of the call
+        """
+    @property
+    def history(self) -> builtins.list[tuple[builtins.int, PyRound, PyAction]]:
+        r"""
+        Actions of the hand as seat, round and action
+        """
+    def __eq__(self, other: builtins.object) -> builtins.bool: ...
+    def __hash__(self) -> builtins.int: ...
+    def encode(self, max_history: builtins.int = 32) -> numpy.typing.NDArray[numpy.float32]:
+        r"""
+        Observation as a float32 NumPy array for neural networks, keeping the last
+        `max_history` actions
+        
+        Holds the hole cards and the board one-hot, the stacks, bets and pot over the chips in
+        play from this seat onwards, the position and the street one-hot, then one row per
+        action of the seat, street and kind one-hot and of the raise amount.
+        """
+    def __repr__(self) -> builtins.str: ...
+
+@typing.final
+class PyPlayerHand:
+    r"""
+    Two hole cards, equal whatever their order
+    """
+    def __eq__(self, other: builtins.object) -> builtins.bool: ...
+    def __hash__(self) -> builtins.int: ...
+    def __new__(cls, text: builtins.str) -> PyPlayerHand: ...
+    def cards(self) -> builtins.list[PyCard]: ...
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
+@typing.final
+class PyPokerEnv:
+    r"""
+    Multi-agent environment following the PettingZoo AEC API, one episode being one hand
+    
+    Agents are named `player_0` onwards after their seat. Observations are dictionaries of the
+    NumPy arrays of the encoded observation, see `PyObservation.encode`, and of the action mask,
+    the `PyObservation` itself being under `state` in the infos. Rewards are the chips won or
+    lost over the hand in big blinds, given to every agent when it ends, at which point every
+    agent is terminated.
+    """
+    @property
+    def metadata(self) -> dict: ...
+    @property
+    def possible_agents(self) -> builtins.list[builtins.str]: ...
+    @property
+    def agents(self) -> builtins.list[builtins.str]:
+        r"""
+        Agents still in the episode, terminated agents leaving once stepped with `None`
+        """
+    @property
+    def num_agents(self) -> builtins.int: ...
+    @property
+    def max_num_agents(self) -> builtins.int: ...
+    @property
+    def agent_selection(self) -> builtins.str:
+        r"""
+        Agent to step next
+        """
+    @property
+    def rewards(self) -> dict:
+        r"""
+        Rewards of the last step
+        """
+    @property
+    def _cumulative_rewards(self) -> dict:
+        r"""
+        Rewards since each agent last acted, as returned by `last`
+        """
+    @property
+    def terminations(self) -> dict: ...
+    @property
+    def truncations(self) -> dict:
+        r"""
+        Always false, a hand never stops before its end
+        """
+    @property
+    def infos(self) -> dict: ...
+    def __new__(cls, settings: PySettings, pot_fractions: typing.Sequence[builtins.float] = [0.5, 1.0, 2.0], max_history: builtins.int = 32) -> PyPokerEnv: ...
+    def reset(self, seed: typing.Optional[builtins.int] = None, options: typing.Optional[dict] = None) -> None:
+        r"""
+        Deals a new hand, the seed making the hands that follow reproducible
+        """
+    def step(self, action: typing.Optional[builtins.int]) -> None:
+        r"""
+        Plays the action of index `action` for the selected agent, `None` once it is terminated
+        
+        Raises `InvalidActionError` when the action mask does not allow the action.
+        """
+    def observe(self, agent: builtins.str) -> dict:
+        r"""
+        Observation of `agent`, as a dictionary of its `PyObservation` and its action mask
+        """
+    def action_mask(self, agent: builtins.str) -> numpy.typing.NDArray[numpy.int8]:
+        r"""
+        One for the actions `agent` can take, all zeros when it is not its turn
+        """
+    def last(self, observe: builtins.bool = True) -> tuple[typing.Optional[dict], builtins.float, builtins.bool, builtins.bool, dict]:
+        r"""
+        Observation, cumulative reward, termination, truncation and info of the selected agent
+        """
+    def agent_iter(self, max_iter: builtins.int = 18446744073709551615) -> PyAgentIter:
+        r"""
+        Agents to step in turn until every agent left the episode
+        """
+    def action_space(self, agent: builtins.str) -> typing.Any:
+        r"""
+        `gymnasium.spaces.Discrete` over the action indices, gymnasium being imported lazily
+        """
+    def observation_space(self, agent: builtins.str) -> typing.Any:
+        r"""
+        `gymnasium.spaces.Dict` of the observations, see `observation_space` of `PyPokerGymEnv`
+        """
+    def close(self) -> None: ...
+
+@typing.final
+class PyPokerGymEnv:
+    r"""
+    Single-agent environment following the Gymnasium API, the other seats played by fixed bots
+    
+    The learner sits in seat 0 and the button moves at random between episodes, one episode
+    being one hand. Observations and rewards are the ones of `PyPokerEnv`. Opponents are named
+    after their bots: `random`, `calling`, `raising`, `tight_aggressive` and `pot_odds`.
+    """
+    @property
+    def action_space(self) -> typing.Any:
+        r"""
+        `gymnasium.spaces.Discrete` over the action indices, gymnasium being imported lazily
+        """
+    @property
+    def observation_space(self) -> typing.Any:
+        r"""
+        `gymnasium.spaces.Dict` of the encoded observation, see `PyObservation.encode`, and of
+        the action mask
+        """
+    def __new__(cls, settings: PySettings, opponents: typing.Sequence[builtins.str], pot_fractions: typing.Sequence[builtins.float] = [0.5, 1.0, 2.0], max_history: builtins.int = 32) -> PyPokerGymEnv:
+        r"""
+        Game of `settings` with one opponent per seat but the first
+        """
+    def reset(self, seed: typing.Optional[builtins.int] = None, options: typing.Optional[dict] = None) -> tuple[dict, dict]:
+        r"""
+        Deals a new hand and lets the bots act until the learner has a decision to make
+        
+        Returns the observation and an info dictionary.
+        """
+    def step(self, action: builtins.int) -> tuple[dict, builtins.float, builtins.bool, builtins.bool, dict]:
+        r"""
+        Plays the action of index `action` for the learner, then the bots until its next turn
+        
+        Returns the observation, the reward, whether the hand is over, whether it was truncated
+        and an info dictionary. Raises `InvalidActionError` when the action mask does not allow
+        the action, and `ValueError` before the first `reset`.
+        """
+    def action_mask(self) -> numpy.typing.NDArray[numpy.int8]:
+        r"""
+        One for the actions the learner can take
+        """
+    def close(self) -> None: ...
+
+@typing.final
+class PyPokerHand:
+    r"""
+    Five cards, comparing and hashing by strength so that hands of equal strength are equal
+    """
+    def __eq__(self, other: builtins.object) -> builtins.bool: ...
+    def __lt__(self, other: builtins.object) -> builtins.bool: ...
+    def __le__(self, other: builtins.object) -> builtins.bool: ...
+    def __gt__(self, other: builtins.object) -> builtins.bool: ...
+    def __ge__(self, other: builtins.object) -> builtins.bool: ...
+    def __hash__(self) -> builtins.int: ...
+    def __new__(cls, text: builtins.str) -> PyPokerHand: ...
+    def hand_type(self) -> PyHandType: ...
+    def cards(self) -> builtins.list[PyCard]: ...
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
+@typing.final
+class PyRake:
+    def __eq__(self, other: builtins.object) -> builtins.bool: ...
+    def __hash__(self) -> builtins.int: ...
+    def __new__(cls, basis_points: builtins.int, caps: typing.Sequence[tuple[builtins.int, builtins.int]] = [], no_flop_no_drop: builtins.bool = False) -> PyRake:
+        r"""
+        `basis_points` hundredths of a percent of the called pot, capped by the `(min_players, cap)`
+        pair of `caps` with the largest `min_players` not exceeding the players dealt in
+        """
+    def __repr__(self) -> builtins.str: ...
 
 @typing.final
 class PySettings:
-    def __new__(cls, n_players: builtins.int, initial_stack: builtins.int) -> PySettings: ...
+    def __new__(cls, n_players: builtins.int, initial_stack: builtins.int, small_blind: builtins.int = 5, big_blind: builtins.int = 10, betting: typing.Optional[PyBettingStructure] = None, rake: typing.Optional[PyRake] = None, symbol: builtins.str = '', decimals: builtins.int = 0) -> PySettings:
+        r"""
+        No-limit and no rake unless others are given, amounts shown as plain chips unless a
+        currency `symbol` and the `decimals` of a chip are given, `"$"` and 2 for cents
+        """
+
+@typing.final
+class PyVecGame:
+    r"""
+    Many independent games stepped together, to collect training data
+    
+    Every game plays hands like `PyPokerEnv`, the seat to act in each game taking the action
+    given for that game, and finished hands are replaced by new ones right away. Games are
+    stepped on several threads with the GIL released, and results come back as NumPy arrays
+    with one row per game. Observations are the ones of the seat to act.
+    """
+    @property
+    def observation_size(self) -> builtins.int:
+        r"""
+        Length of the observations, see `PyObservation.encode`
+        """
+    @property
+    def n_actions(self) -> builtins.int:
+        r"""
+        Length of the action masks, actions being indexed as in `PyPokerEnv`
+        """
+    def __new__(cls, settings: PySettings, n_games: builtins.int, pot_fractions: typing.Sequence[builtins.float] = [0.5, 1.0, 2.0], max_history: builtins.int = 32, seed: typing.Optional[builtins.int] = None) -> PyVecGame:
+        r"""
+        `n_games` games of `settings`, game `i` being seeded with `seed + i` when a seed is given
+        """
+    def __len__(self) -> builtins.int: ...
+    def reset(self, seed: typing.Optional[builtins.int] = None) -> tuple[numpy.typing.NDArray[numpy.float32], numpy.typing.NDArray[numpy.int8], numpy.typing.NDArray[numpy.int64]]:
+        r"""
+        Deals a new hand in every game
+        
+        Returns the observations, the action masks and the seats to act.
+        """
+    def step(self, actions: numpy.typing.NDArray[numpy.int64]) -> tuple[numpy.typing.NDArray[numpy.float32], numpy.typing.NDArray[numpy.int8], numpy.typing.NDArray[numpy.float32], typing.Any, numpy.typing.NDArray[numpy.int64]]:
+        r"""
+        Plays one action in every game, given as an int64 array of action indices
+        
+        Returns the observations, the action masks, the rewards of every seat in big blinds,
+        whether each hand ended and the seats to act. The observations of the games whose hand
+        ended are the ones of the next hand. Raises `InvalidActionError` without playing any
+        action when an action mask does not allow the action of its game.
+        """
+
+@typing.final
+class PyHandType(enum.Enum):
+    r"""
+    Hand types from the weakest to the strongest
+    """
+    HighCard = ...
+    Pair = ...
+    DoublePair = ...
+    ThreeOfAKind = ...
+    Straight = ...
+    Flush = ...
+    FullHouse = ...
+    FourOfAKind = ...
+    StraightFlush = ...
+
+@typing.final
+class PyRound(enum.Enum):
+    PreFlop = ...
+    Flop = ...
+    Turn = ...
+    River = ...
 
@@ -0,0 +1,158 @@
+use crate::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// A participant of the game, choosing the actions of one seat
+///
+/// Only `choose_action` is required, the hooks let an agent follow the hand as it goes.
+pub trait Agent {
+    /// Chooses an action for the seat to act, from what this seat can observe
+    fn choose_action(&mut self, state: &ObservableState, legal_actions: &LegalActions) -> Action;
+
+    /// Probabilities `choose_action` draws its action with, for agents whose policy is known
+    ///
+    /// Evaluations use them to remove the luck of the agent's own sampling from its results.
+    fn action_probabilities(
+        &mut self,
+        _state: &ObservableState,
+        _legal_actions: &LegalActions,
+    ) -> Option<Vec<(Action, f64)>> {
+        None
+    }
+
+    /// Called for every seat once the cards of a new hand are dealt
+    fn on_hand_start(&mut self, _state: &ObservableState) {}
+
+    /// Called for every seat after any seat acted, including itself
+    fn on_action_observed(&mut self, _record: &ActionRecord) {}
+
+    /// Called for every seat when the hand goes to showdown, before `on_hand_end`
+    fn on_showdown(&mut self, _result: &HandResult) {}
+
+    fn on_hand_end(&mut self, _result: &HandResult) {}
+}
+
+/// Plays by sampling the actions of a policy
+pub struct PolicyAgent<P> {
+    policy: P,
+    rng: StdRng,
+}
+
+impl<P: Policy> PolicyAgent<P> {
+    pub fn new(policy: P, seed: u64) -> Self {
+        PolicyAgent {
+            policy,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl<P: Policy> PolicyAgent<P> {
+    /// Probabilities of the policy, the weight of illegal actions going to checking or calling
+    fn legal_probabilities(
+        &self,
+        state: &ObservableState,
+        legal_actions: &LegalActions,
+    ) -> Vec<(Action, f64)> {
+        let passive = if legal_actions.can_check() {
+            Action::Check
+        } else {
+            Action::Call
+        };
+        let mut probabilities: Vec<(Action, f64)> = Vec::new();
+        for (action, probability) in self.policy.action_probabilities(state) {
+            let action = if legal_actions.contains(action) {
+                action
+            } else {
+                passive
+            };
+            match probabilities.iter_mut().find(|(a, _)| *a == action) {
+                Some((_, p)) => *p += probability,
+                None => probabilities.push((action, probability)),
+            }
+        }
+        if probabilities.iter().all(|(_, p)| *p <= 0.0) {
+            probabilities = vec![(passive, 1.0)];
+        }
+        probabilities
+    }
+}
+
+impl<P: Policy> Agent for PolicyAgent<P> {
+    fn choose_action(&mut self, state: &ObservableState, legal_actions: &LegalActions) -> Action {
+        let probabilities = self.legal_probabilities(state, legal_actions);
+        // never empty, so an action is always drawn
+        sample_action(&probabilities, &mut self.rng).unwrap()
+    }
+
+    fn action_probabilities(
+        &mut self,
+        state: &ObservableState,
+        legal_actions: &LegalActions,
+    ) -> Option<Vec<(Action, f64)>> {
+        Some(self.legal_probabilities(state, legal_actions))
+    }
+}
+
+/// Plays the current hand to its end with one agent per seat, keeping every agent informed
+///
+/// Fails with `GameError::AgentCountMismatch` when there is not exactly one agent per seat.
+pub fn play_hand(game: &mut Game, agents: &mut [Box<dyn Agent>]) -> Result<HandResult, GameError> {
+    start_hand(game, agents)?;
+    let hand = game.hand_count();
+    while game.hand_count() == hand {
+        let state = game.get_observable_state(game.current_seat());
+        let action = agents[state.seat].choose_action(&state, &state.legal_actions());
+        play_action(game, agents, &state, action)?;
+    }
+    Ok(end_hand(game, agents))
+}
+
+/// Tells every agent about the hand about to be played
+pub(crate) fn start_hand(game: &Game, agents: &mut [Box<dyn Agent>]) -> Result<(), GameError> {
+    if agents.len() != game.settings().n_players {
+        return Err(GameError::AgentCountMismatch {
+            expected: game.settings().n_players,
+            got: agents.len(),
+        });
+    }
+    if game.over() {
+        return Err(GameError::GameOver);
+    }
+    for (seat, agent) in agents.iter_mut().enumerate() {
+        agent.on_hand_start(&game.get_observable_state(seat));
+    }
+    Ok(())
+}
+
+/// Plays the action chosen by the seat to act in `state` and shows it to every agent
+pub(crate) fn play_action(
+    game: &mut Game,
+    agents: &mut [Box<dyn Agent>],
+    state: &ObservableState,
+    action: Action,
+) -> Result<(), GameError> {
+    game.play_turn(action)?;
+    let record = ActionRecord {
+        seat: state.seat,
+        round: state.round,
+        action,
+    };
+    for agent in agents.iter_mut() {
+        agent.on_action_observed(&record);
+    }
+    Ok(())
+}
+
+/// Shows the result of the hand that just ended to every agent
+pub(crate) fn end_hand(game: &Game, agents: &mut [Box<dyn Agent>]) -> HandResult {
+    // the hand count only moves forward once a result is recorded
+    let result = game.last_hand_result().cloned().unwrap_or_default();
+    for agent in agents.iter_mut() {
+        if result.showdown {
+            agent.on_showdown(&result);
+        }
+        agent.on_hand_end(&result);
+    }
+    result
+}
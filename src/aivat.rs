@@ -0,0 +1,274 @@
+use crate::*;
+use rand::{RngCore, seq::SliceRandom};
+
+/// Everything about a hand in progress, hidden cards included
+#[derive(Clone, Debug)]
+pub struct HandView {
+    pub hands: Vec<Option<PlayerHand>>,
+    pub board: Vec<Card>,
+    /// Chips every seat put in the pot over the hand
+    pub committed: Vec<Chips>,
+    /// Seats that folded or are out of the game
+    pub folded: Vec<bool>,
+}
+
+impl HandView {
+    /// View of the hand currently played by `game`
+    pub fn new(game: &Game) -> Self {
+        let state = game.get_observable_state(game.current_seat());
+        HandView {
+            hands: game.hands().to_vec(),
+            board: state.board,
+            committed: state
+                .starting_stacks
+                .iter()
+                .zip(&state.seats)
+                .map(|(&start, seat)| start - seat.stack)
+                .collect(),
+            folded: state.seats.iter().map(|seat| !seat.is_valid()).collect(),
+        }
+    }
+
+    /// View once the seat to act in `state` played `action`, before any card is dealt
+    pub fn after_action(&self, state: &ObservableState, action: Action) -> Self {
+        let seat = state.current_seat;
+        let stack = state.seats[seat].stack;
+        let call_amount = state.legal_actions().call_amount;
+        let mut view = self.clone();
+        match action {
+            Action::Fold => view.folded[seat] = true,
+            Action::Check => {}
+            Action::Call => view.committed[seat] += call_amount.min(stack),
+            Action::Raise(amount) => view.committed[seat] += (call_amount + amount).min(stack),
+        }
+        view
+    }
+
+    /// Seats still holding cards, with their cards
+    pub fn live_hands(&self) -> Vec<(usize, PlayerHand)> {
+        self.hands
+            .iter()
+            .zip(&self.folded)
+            .enumerate()
+            .filter(|(_, (_, folded))| !**folded)
+            .filter_map(|(seat, (hand, _))| hand.map(|hand| (seat, hand)))
+            .collect()
+    }
+
+    fn known_cards(&self) -> Vec<Card> {
+        self.hands
+            .iter()
+            .flatten()
+            .flat_map(|hand| hand.cards().copied().collect::<Vec<_>>())
+            .chain(self.board.iter().copied())
+            .collect()
+    }
+}
+
+/// Cards about to be dealt in a hand
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Deal {
+    HoleCards,
+    /// Number of board cards
+    Board(usize),
+}
+
+impl Deal {
+    /// Draws the cards of the deal on top of `view`
+    fn sample(&self, view: &HandView, rng: &mut dyn RngCore) -> HandView {
+        let mut dealt = view.clone();
+        match *self {
+            Deal::HoleCards => {
+                let mut deck = remaining_cards(view.board.iter());
+                deck.shuffle(rng);
+                let mut cards = deck.into_iter();
+                for hand in dealt.hands.iter_mut().flatten() {
+                    // a deck holds enough cards for every seat
+                    *hand = PlayerHand::new(cards.next().unwrap(), cards.next().unwrap());
+                }
+            }
+            Deal::Board(count) => {
+                let mut remaining = remaining_cards(view.known_cards().iter());
+                let (drawn, _) = remaining.partial_shuffle(rng, count);
+                dealt.board.extend_from_slice(drawn);
+            }
+        }
+        dealt
+    }
+}
+
+/// Estimates the chips every seat is expected to win or lose over a hand
+pub trait ValueEstimator: Send + Sync {
+    fn values(&self, view: &HandView, rng: &mut dyn RngCore) -> Vec<f64>;
+
+    /// Values expected over the cards of `deal`, before they are added to `view`
+    ///
+    /// Defaults to the mean of the values of `samples` draws, estimators able to average over
+    /// the cards themselves should do it instead.
+    fn expected_values(
+        &self,
+        view: &HandView,
+        deal: Deal,
+        samples: usize,
+        rng: &mut dyn RngCore,
+    ) -> Vec<f64> {
+        let mut mean = vec![0.0; view.hands.len()];
+        for _ in 0..samples.max(1) {
+            let values = self.values(&deal.sample(view, rng), rng);
+            for (mean, value) in mean.iter_mut().zip(values) {
+                *mean += value / samples.max(1) as f64;
+            }
+        }
+        mean
+    }
+}
+
+/// Values every seat by its share of the pot at showdown, as if no more chips went in
+///
+/// Side pots are ignored. The river is evaluated exactly, earlier rounds by sampling `samples`
+/// run-outs.
+#[derive(Clone, Copy, Debug)]
+pub struct ShowdownEstimator {
+    pub samples: usize,
+}
+
+impl ValueEstimator for ShowdownEstimator {
+    fn values(&self, view: &HandView, rng: &mut dyn RngCore) -> Vec<f64> {
+        let pot: Chips = view.committed.iter().sum();
+        let live = view.live_hands();
+        let mut shares = vec![0.0; view.hands.len()];
+
+        if live.len() == 1 {
+            shares[live[0].0] = 1.0;
+        } else if live.len() > 1 {
+            let mut remaining = remaining_cards(view.known_cards().iter());
+            let missing = 5 - view.board.len();
+            let runs = if missing == 0 { 1 } else { self.samples.max(1) };
+            for _ in 0..runs {
+                let (drawn, _) = remaining.partial_shuffle(rng, missing);
+                let board: Vec<Card> = view.board.iter().chain(drawn.iter()).copied().collect();
+                let ranks: Vec<HandRank> = live
+                    .iter()
+                    .map(|(_, hand)| HandRank::evaluate(hand.cards().chain(&board)))
+                    .collect();
+                // at least one hand is live
+                let best = *ranks.iter().max().unwrap();
+                let winners = ranks.iter().filter(|&&rank| rank == best).count();
+                for ((seat, _), rank) in live.iter().zip(&ranks) {
+                    if *rank == best {
+                        shares[*seat] += 1.0 / (winners * runs) as f64;
+                    }
+                }
+            }
+        }
+
+        shares
+            .iter()
+            .zip(&view.committed)
+            .map(|(share, &committed)| share * pot.as_f64() - committed.as_f64())
+            .collect()
+    }
+
+    /// Random hands share the pot equally, and the values already average over the run-outs
+    fn expected_values(
+        &self,
+        view: &HandView,
+        deal: Deal,
+        _samples: usize,
+        rng: &mut dyn RngCore,
+    ) -> Vec<f64> {
+        match deal {
+            Deal::HoleCards => {
+                let pot: Chips = view.committed.iter().sum();
+                let live = view.live_hands();
+                let mut values: Vec<f64> = view.committed.iter().map(|&c| -c.as_f64()).collect();
+                for (seat, _) in &live {
+                    values[*seat] += pot.as_f64() / live.len() as f64;
+                }
+                values
+            }
+            Deal::Board(_) => self.values(view, rng),
+        }
+    }
+}
+
+fn add_difference(total: &mut [f64], after: &[f64], before: &[f64]) {
+    for ((total, after), before) in total.iter_mut().zip(after).zip(before) {
+        *total += after - before;
+    }
+}
+
+/// Plays the current hand like `play_hand`, also returning the winnings of every seat with the
+/// luck of the cards and of the agents revealing their policy taken out
+///
+/// These are the control variates of AIVAT (Burch et al.): every card dealt and every action of
+/// a known policy adds the value after it minus the value expected before it, as given by
+/// `estimator`. The corrections average to zero, so the corrected winnings stay unbiased
+/// whatever the quality of the estimator. Expected values over cards are estimated from
+/// `samples` draws.
+pub fn play_hand_aivat(
+    game: &mut Game,
+    agents: &mut [Box<dyn Agent>],
+    estimator: &dyn ValueEstimator,
+    samples: usize,
+    rng: &mut dyn RngCore,
+) -> Result<(HandResult, Vec<f64>), GameError> {
+    start_hand(game, agents)?;
+    let hand = game.hand_count();
+    let n_seats = game.hands().len();
+    let mut correction = vec![0.0; n_seats];
+
+    // hole cards
+    let mut view = HandView::new(game);
+    let expected = estimator.expected_values(&view, Deal::HoleCards, samples, rng);
+    add_difference(&mut correction, &estimator.values(&view, rng), &expected);
+
+    while game.hand_count() == hand {
+        let state = game.get_observable_state(game.current_seat());
+        let legal_actions = state.legal_actions();
+        let agent = &mut agents[state.seat];
+        let probabilities = agent.action_probabilities(&state, &legal_actions);
+        let action = agent.choose_action(&state, &legal_actions);
+
+        let mut next = view.after_action(&state, action);
+        if let Some(probabilities) = probabilities {
+            let mut expected = vec![0.0; n_seats];
+            for (other, probability) in probabilities {
+                let values = estimator.values(&view.after_action(&state, other), rng);
+                for (expected, value) in expected.iter_mut().zip(values) {
+                    *expected += probability * value;
+                }
+            }
+            add_difference(&mut correction, &estimator.values(&next, rng), &expected);
+        }
+
+        play_action(game, agents, &state, action)?;
+        let board = match game.last_hand_result() {
+            Some(result) if game.hand_count() != hand => result.board.clone(),
+            _ => game.get_observable_state(0).board,
+        };
+
+        // every street dealt by the action, the whole run-out after an all-in
+        while next.board.len() < board.len() {
+            let dealt = if next.board.is_empty() { 3 } else { 1 };
+            let expected = estimator.expected_values(&next, Deal::Board(dealt), samples, rng);
+            next.board = board[..next.board.len() + dealt].to_vec();
+            add_difference(&mut correction, &estimator.values(&next, rng), &expected);
+        }
+
+        view = if game.hand_count() == hand {
+            HandView::new(game)
+        } else {
+            next
+        };
+    }
+
+    let result = end_hand(game, agents);
+    let corrected = result
+        .deltas
+        .iter()
+        .zip(&correction)
+        .map(|(&delta, correction)| delta as f64 - correction)
+        .collect();
+    Ok((result, corrected))
+}
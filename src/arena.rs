@@ -0,0 +1,262 @@
+use crate::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use std::sync::Arc;
+
+/// Builds the agent of one seat from a seed
+pub type AgentFactory = Box<dyn Fn(u64) -> Box<dyn Agent> + Send + Sync>;
+
+/// Plays agents against each other over many hands and measures their win rates
+///
+/// Every hand starts from fresh stacks and the agents move one seat further after each hand,
+/// so they all play every position equally often. Tables run in parallel, each one seeded from
+/// `seed`, so a match always plays out the same way.
+#[derive(Clone, Default)]
+pub struct Arena {
+    /// Game played at every table, with one seat per agent
+    pub settings: Settings,
+    /// Hands played at each table
+    pub hands: usize,
+    pub tables: usize,
+    pub seed: u64,
+    /// Replays every deal once per seat rotation, so every agent plays the cards of every seat
+    ///
+    /// `hands` should then be a multiple of the number of agents.
+    pub duplicate: bool,
+    /// Value estimator used to take the luck out of the results with AIVAT
+    pub estimator: Option<Arc<dyn ValueEstimator>>,
+    /// Draws estimating the expected values of AIVAT
+    pub estimator_samples: usize,
+}
+
+/// Win rate of one agent over a match
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AgentResult {
+    pub hands: usize,
+    pub bb_per_100: f64,
+    /// Half width of the 95% confidence interval around `bb_per_100`
+    pub confidence: f64,
+}
+
+impl AgentResult {
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        (
+            self.bb_per_100 - self.confidence,
+            self.bb_per_100 + self.confidence,
+        )
+    }
+}
+
+/// Running sums of the winnings of one agent, in big blinds
+///
+/// Every sample is the winnings of a group of hands: a single hand, or all the rotations of a
+/// duplicate deal, which are not independent of each other.
+#[derive(Clone, Copy, Default)]
+struct Winnings {
+    hands: usize,
+    samples: usize,
+    sum: f64,
+    sum_of_squares: f64,
+}
+
+impl Winnings {
+    fn add(&mut self, big_blinds: f64, hands: usize) {
+        self.hands += hands;
+        self.samples += 1;
+        self.sum += big_blinds;
+        self.sum_of_squares += big_blinds * big_blinds;
+    }
+
+    fn merge(mut self, other: Winnings) -> Winnings {
+        self.hands += other.hands;
+        self.samples += other.samples;
+        self.sum += other.sum;
+        self.sum_of_squares += other.sum_of_squares;
+        self
+    }
+
+    fn result(&self) -> AgentResult {
+        if self.hands == 0 {
+            return AgentResult::default();
+        }
+        let n = self.samples as f64;
+        let mean = self.sum / n;
+        let variance = (self.sum_of_squares / n - mean * mean).max(0.0) * n / (n - 1.0).max(1.0);
+        let hands_per_sample = self.hands as f64 / n;
+        AgentResult {
+            hands: self.hands,
+            bb_per_100: 100.0 * mean / hands_per_sample,
+            confidence: 100.0 * 1.96 * (variance / n).sqrt() / hands_per_sample,
+        }
+    }
+}
+
+impl Arena {
+    /// Plays the match between the agents built by `agents`, one per seat, and returns the win
+    /// rate of each
+    ///
+    /// Fails with `GameError::InvalidSettings` when the settings with one seat per agent do not
+    /// validate, fewer than two agents included.
+    pub fn run(&self, agents: &[AgentFactory]) -> Result<Vec<AgentResult>, GameError> {
+        let settings = Settings {
+            n_players: agents.len(),
+            ..self.settings.clone()
+        };
+        settings.validate()?;
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let table_seeds: Vec<u64> = (0..self.tables).map(|_| rng.random()).collect();
+
+        let tables = table_seeds
+            .into_par_iter()
+            .map(|seed| self.play_table(&settings, agents, seed))
+            .collect::<Result<Vec<_>, GameError>>()?;
+
+        let winnings =
+            tables
+                .into_iter()
+                .fold(vec![Winnings::default(); agents.len()], |total, table| {
+                    total
+                        .into_iter()
+                        .zip(table)
+                        .map(|(total, table)| total.merge(table))
+                        .collect()
+                });
+        Ok(winnings.iter().map(Winnings::result).collect())
+    }
+
+    fn play_table(
+        &self,
+        settings: &Settings,
+        factories: &[AgentFactory],
+        seed: u64,
+    ) -> Result<Vec<Winnings>, GameError> {
+        let n = factories.len();
+        let mut rng = StdRng::seed_from_u64(seed);
+        // agents[seat] is the agent built by factories[(seat + n - hand % n) % n]
+        let mut agents: Vec<Box<dyn Agent>> = factories
+            .iter()
+            .map(|factory| factory(rng.random()))
+            .collect();
+        let mut estimator_rng = StdRng::seed_from_u64(rng.random());
+        let mut winnings = vec![Winnings::default(); n];
+        // winnings of every agent over the hands of the current deal
+        let mut group = vec![0.0; n];
+        let mut group_hands = 0;
+        let mut deal_seed = 0;
+
+        for hand in 0..self.hands {
+            if !self.duplicate || hand % n == 0 {
+                deal_seed = rng.random();
+            }
+            let mut game = Game::with_seed(settings.clone(), deal_seed)?;
+            // blinds can decide the first hand before anyone acts
+            let values: Vec<f64> = match (game.last_hand_result(), &self.estimator) {
+                (Some(result), _) => result.deltas.iter().map(|&delta| delta as f64).collect(),
+                (None, Some(estimator)) => {
+                    play_hand_aivat(
+                        &mut game,
+                        &mut agents,
+                        estimator.as_ref(),
+                        self.estimator_samples,
+                        &mut estimator_rng,
+                    )?
+                    .1
+                }
+                (None, None) => play_hand(&mut game, &mut agents)?
+                    .deltas
+                    .iter()
+                    .map(|&delta| delta as f64)
+                    .collect(),
+            };
+            for (seat, value) in values.iter().enumerate() {
+                let agent = (seat + n - hand % n) % n;
+                group[agent] += value / settings.big_blind.as_f64();
+            }
+            group_hands += 1;
+            if !self.duplicate || (hand + 1) % n == 0 || hand + 1 == self.hands {
+                for (winnings, value) in winnings.iter_mut().zip(&mut group) {
+                    winnings.add(std::mem::take(value), group_hands);
+                }
+                group_hands = 0;
+            }
+            agents.rotate_right(1);
+        }
+        Ok(winnings)
+    }
+}
+
+#[test]
+fn arena_is_deterministic_and_zero_sum() {
+    let arena = Arena {
+        settings: Settings {
+            n_players: 2,
+            initial_stack: Chips::new(1000),
+            small_blind: Chips::new(5),
+            big_blind: Chips::new(10),
+            ..Default::default()
+        },
+        hands: 200,
+        tables: 4,
+        seed: 7,
+        ..Default::default()
+    };
+    let agents: Vec<AgentFactory> = vec![
+        Box::new(|seed| Box::new(RandomAgent::new(seed))),
+        Box::new(|_| Box::new(CallingAgent)),
+    ];
+
+    let results = arena.run(&agents).unwrap();
+    assert_eq!(results, arena.run(&agents).unwrap());
+    assert_eq!(results[0].hands, 800);
+    assert!((results[0].bb_per_100 + results[1].bb_per_100).abs() < 1e-9);
+    assert!(results[0].confidence > 0.0);
+
+    // a match needs two agents at least
+    assert!(matches!(arena.run(&[]), Err(GameError::InvalidSettings)));
+    assert!(matches!(
+        arena.run(&agents[..1]),
+        Err(GameError::InvalidSettings)
+    ));
+}
+
+#[test]
+fn duplicate_and_aivat_reduce_variance() {
+    let plain = Arena {
+        settings: Settings {
+            n_players: 2,
+            initial_stack: Chips::new(200),
+            small_blind: Chips::new(5),
+            big_blind: Chips::new(10),
+            ..Default::default()
+        },
+        hands: 200,
+        tables: 2,
+        seed: 3,
+        ..Default::default()
+    };
+    let callers: Vec<AgentFactory> = vec![
+        Box::new(|_| Box::new(CallingAgent)),
+        Box::new(|_| Box::new(CallingAgent)),
+    ];
+
+    // identical deterministic agents break exactly even over duplicate deals
+    let duplicate = Arena {
+        duplicate: true,
+        ..plain.clone()
+    };
+    let results = duplicate.run(&callers).unwrap();
+    assert_eq!(results[0].hands, 400);
+    assert!(results[0].bb_per_100.abs() < 1e-9);
+    assert!(results[0].confidence < 1e-9);
+
+    // checking down leaves only the luck of the cards, which the corrections remove
+    let aivat = Arena {
+        estimator: Some(Arc::new(ShowdownEstimator { samples: 200 })),
+        estimator_samples: 8,
+        ..plain.clone()
+    };
+    let noisy = plain.run(&callers).unwrap();
+    let corrected = aivat.run(&callers).unwrap();
+    assert!(corrected[0].confidence < noisy[0].confidence / 4.0);
+}
@@ -0,0 +1,229 @@
+use crate::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Opponents still holding cards, as seen from the observing seat
+fn opponents_in_hand(state: &ObservableState) -> usize {
+    state
+        .seats
+        .iter()
+        .enumerate()
+        .filter(|(i, seat)| *i != state.seat && seat.is_valid())
+        .count()
+}
+
+fn check_or_call(legal_actions: &LegalActions) -> Action {
+    if legal_actions.can_check() {
+        Action::Check
+    } else {
+        Action::Call
+    }
+}
+
+fn check_or_fold(legal_actions: &LegalActions) -> Action {
+    if legal_actions.can_check() {
+        Action::Check
+    } else {
+        Action::Fold
+    }
+}
+
+/// Picks uniformly between folding, checking or calling and raising, then uniformly among the
+/// raise amounts
+///
+/// Folding is only considered when there is something to call.
+pub struct RandomAgent {
+    rng: StdRng,
+}
+
+impl RandomAgent {
+    pub fn new(seed: u64) -> Self {
+        RandomAgent {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Agent for RandomAgent {
+    fn choose_action(&mut self, _state: &ObservableState, legal_actions: &LegalActions) -> Action {
+        let mut actions = vec![check_or_call(legal_actions)];
+        if !legal_actions.can_check() {
+            actions.push(Action::Fold);
+        }
+        if let Some((min, max)) = legal_actions.raise_range {
+            actions.push(Action::Raise(Chips::new(
+                self.rng.random_range(min.get()..=max.get()),
+            )));
+        }
+        actions[self.rng.random_range(0..actions.len())]
+    }
+}
+
+/// Checks or calls every time
+#[derive(Clone, Copy, Default)]
+pub struct CallingAgent;
+
+impl Agent for CallingAgent {
+    fn choose_action(&mut self, _state: &ObservableState, legal_actions: &LegalActions) -> Action {
+        check_or_call(legal_actions)
+    }
+}
+
+/// Makes the smallest raise every time, calling when raising is not possible
+#[derive(Clone, Copy, Default)]
+pub struct RaisingAgent;
+
+impl Agent for RaisingAgent {
+    fn choose_action(&mut self, _state: &ObservableState, legal_actions: &LegalActions) -> Action {
+        match legal_actions.raise_range {
+            Some((min, _)) => Action::Raise(min),
+            None => check_or_call(legal_actions),
+        }
+    }
+}
+
+/// Rule based player: opens a tight preflop chart and bets its hand strength after the flop
+///
+/// Preflop, hands of the raising chart raise by twice the chips to call, two big blinds at
+/// least, so they open to three big blinds, and hands of the calling chart call. After the flop, the equity against the opponents left decides: above
+/// `raise_threshold` it bets three quarters of the pot, above `call_threshold` or the pot odds it
+/// calls, otherwise it gives up.
+pub struct TightAggressiveAgent {
+    pub raise_threshold: f64,
+    pub call_threshold: f64,
+    pub equity_samples: usize,
+    rng: StdRng,
+}
+
+impl TightAggressiveAgent {
+    pub fn new(seed: u64) -> Self {
+        TightAggressiveAgent {
+            raise_threshold: 0.75,
+            call_threshold: 0.55,
+            equity_samples: 200,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// 77+, ATs+, KJs+, QJs, AJo+ and KQo
+    pub fn raising_chart(class: &HandClass) -> bool {
+        let (high, low) = (class.high.number_value(), class.low.number_value());
+        if class.is_pair() {
+            high >= 7
+        } else if class.suited {
+            (high == 14 && low >= 10) || (high == 13 && low >= 11) || (high == 12 && low == 11)
+        } else {
+            (high == 14 && low >= 11) || (high == 13 && low == 12)
+        }
+    }
+
+    /// Raising chart plus 22+, A2s+, KTs+, QTs+, JTs, T9s, 98s, ATo+ and KJo+
+    pub fn calling_chart(class: &HandClass) -> bool {
+        let (high, low) = (class.high.number_value(), class.low.number_value());
+        if class.is_pair() {
+            true
+        } else if class.suited {
+            high == 14 || (high >= 12 && low >= 10) || (high - low == 1 && low >= 8)
+        } else {
+            (high == 14 && low >= 10) || (high == 13 && low >= 11)
+        }
+    }
+
+    fn raise(legal_actions: &LegalActions, amount: Chips) -> Action {
+        match legal_actions.raise_range {
+            Some((min, max)) => Action::Raise(amount.clamp(min, max)),
+            None => check_or_call(legal_actions),
+        }
+    }
+}
+
+impl Agent for TightAggressiveAgent {
+    fn choose_action(&mut self, state: &ObservableState, legal_actions: &LegalActions) -> Action {
+        let Some(hand) = state.hand else {
+            return check_or_fold(legal_actions);
+        };
+        let call_amount = legal_actions.call_amount;
+
+        if state.round == Round::PreFlop {
+            let class = HandClass::from_hand(&hand);
+            return if Self::raising_chart(&class) {
+                Self::raise(legal_actions, 2 * call_amount.max(state.big_blind))
+            } else if Self::calling_chart(&class) {
+                check_or_call(legal_actions)
+            } else {
+                check_or_fold(legal_actions)
+            };
+        }
+
+        let equity = equity_vs_random(
+            &hand,
+            &state.board,
+            opponents_in_hand(state).max(1),
+            self.equity_samples,
+            &mut self.rng,
+        );
+        let pot = state.total_pot() + call_amount;
+        let pot_odds = call_amount.as_f64() / pot.get().max(1) as f64;
+        if equity >= self.raise_threshold {
+            Self::raise(legal_actions, 3 * pot / 4)
+        } else if equity >= self.call_threshold.min(pot_odds) {
+            check_or_call(legal_actions)
+        } else {
+            check_or_fold(legal_actions)
+        }
+    }
+}
+
+/// Calls whenever its equity against the opponents left covers the pot odds, never raises
+pub struct PotOddsAgent {
+    pub equity_samples: usize,
+    rng: StdRng,
+}
+
+impl PotOddsAgent {
+    pub fn new(seed: u64) -> Self {
+        PotOddsAgent {
+            equity_samples: 200,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Agent for PotOddsAgent {
+    fn choose_action(&mut self, state: &ObservableState, legal_actions: &LegalActions) -> Action {
+        let Some(hand) = state.hand else {
+            return check_or_fold(legal_actions);
+        };
+        if legal_actions.can_check() {
+            return Action::Check;
+        }
+
+        let equity = equity_vs_random(
+            &hand,
+            &state.board,
+            opponents_in_hand(state).max(1),
+            self.equity_samples,
+            &mut self.rng,
+        );
+        let pot_odds = legal_actions.call_amount.as_f64()
+            / (state.total_pot() + legal_actions.call_amount).as_f64();
+        if equity >= pot_odds {
+            Action::Call
+        } else {
+            Action::Fold
+        }
+    }
+}
+
+#[test]
+fn tight_aggressive_charts() {
+    let raising: Vec<HandClass> = HandClass::all()
+        .filter(TightAggressiveAgent::raising_chart)
+        .collect();
+    assert!(raising.iter().all(TightAggressiveAgent::calling_chart));
+    let combos: usize = raising.iter().map(|class| class.combos()).sum();
+    assert_eq!(combos, 8 * 6 + 4 * 4 + 2 * 4 + 4 + 3 * 12 + 12);
+    assert!(!TightAggressiveAgent::calling_chart(
+        &HandClass::from_index(13 * 12 + 7)
+    ));
+}
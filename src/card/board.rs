@@ -2,11 +2,19 @@ use itertools::Itertools;
 
 use crate::card::*;
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Board {
     card_count: usize,
     cards: [Option<Card>; 5],
 }
 
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Board {
     pub fn new() -> Self {
         Self {
@@ -33,6 +41,10 @@ impl Board {
         Ok(best_hand.unwrap())
     }
 
+    pub fn hand_rank(&self, player_hand: &PlayerHand) -> HandRank {
+        HandRank::evaluate(player_hand.cards().chain(self.cards()))
+    }
+
     pub fn add_card(&mut self, card: Card) -> Result<(), CardError> {
         if self.card_count >= 5 {
             return Err(CardError::BoardOverflow);
@@ -42,11 +54,20 @@ impl Board {
         Ok(())
     }
 
+    /// Takes back the last card dealt
+    pub fn remove_card(&mut self) -> Option<Card> {
+        if self.card_count == 0 {
+            return None;
+        }
+        self.card_count -= 1;
+        self.cards[self.card_count].take()
+    }
+
     pub fn card_count(&self) -> usize {
         self.card_count
     }
 
-    pub fn cards<'a>(&'a self) -> impl Iterator<Item = &'a Card> {
+    pub fn cards(&self) -> impl Iterator<Item = &Card> {
         self.cards.iter().filter_map(|c| c.as_ref())
     }
 }
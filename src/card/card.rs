@@ -1,4 +1,7 @@
-#[derive(Clone, Copy, Debug)]
+use crate::card::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Card {
     pub suit: Suit,
     pub value: Value,
@@ -8,9 +11,71 @@ impl Card {
     pub fn new(suit: Suit, value: Value) -> Self {
         Card { suit, value }
     }
+
+    /// Unique index of the card in `0..52`
+    pub fn index(&self) -> usize {
+        self.suit.index() * 13 + self.value.index()
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Card {
+            suit: Suit::from_index(index / 13),
+            value: Value::from_index(index % 13),
+        }
+    }
+
+    /// Cards written back to back, such as `AsKd`, spaces allowed
+    pub fn parse_list(text: &str) -> Result<Vec<Card>, CardError> {
+        let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+        if !chars.len().is_multiple_of(2) {
+            return Err(CardError::InvalidCard(text.to_string()));
+        }
+        chars
+            .chunks(2)
+            .map(|card| card.iter().collect::<String>().parse())
+            .collect()
+    }
+}
+
+/// Parses a value and a suit such as `As` or `td`
+impl std::str::FromStr for Card {
+    type Err = CardError;
+
+    fn from_str(text: &str) -> Result<Self, CardError> {
+        let invalid = || CardError::InvalidCard(text.to_string());
+        let mut chars = text.chars();
+        let (Some(value), Some(suit), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(invalid());
+        };
+        let value = match value.to_ascii_uppercase() {
+            'A' => Value::Ace,
+            'K' => Value::King,
+            'Q' => Value::Queen,
+            'J' => Value::Jack,
+            'T' => Value::Ten,
+            '9' => Value::Nine,
+            '8' => Value::Eight,
+            '7' => Value::Seven,
+            '6' => Value::Six,
+            '5' => Value::Five,
+            '4' => Value::Four,
+            '3' => Value::Three,
+            '2' => Value::Two,
+            _ => return Err(invalid()),
+        };
+        let suit = match suit.to_ascii_lowercase() {
+            's' => Suit::Spades,
+            'h' => Suit::Hearts,
+            'd' => Suit::Diamonds,
+            'c' => Suit::Clubs,
+            _ => return Err(invalid()),
+        };
+        Ok(Card::new(suit, value))
+    }
 }
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Value {
     Ace,
     King,
@@ -47,12 +112,6 @@ impl Value {
     }
 }
 
-impl PartialEq for Card {
-    fn eq(&self, other: &Self) -> bool {
-        self.suit == other.suit && self.value == other.value
-    }
-}
-
 impl PartialOrd for Card {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         self.value
@@ -61,12 +120,50 @@ impl PartialOrd for Card {
     }
 }
 
-impl Eq for Card {}
-
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Suit {
     Spades,
     Diamonds,
     Clubs,
     Hearts,
 }
+
+impl std::fmt::Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.value, self.suit)
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Value::Ace => 'A',
+            Value::King => 'K',
+            Value::Queen => 'Q',
+            Value::Jack => 'J',
+            Value::Ten => 'T',
+            Value::Nine => '9',
+            Value::Eight => '8',
+            Value::Seven => '7',
+            Value::Six => '6',
+            Value::Five => '5',
+            Value::Four => '4',
+            Value::Three => '3',
+            Value::Two => '2',
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+impl std::fmt::Display for Suit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Suit::Spades => 's',
+            Suit::Diamonds => 'd',
+            Suit::Clubs => 'c',
+            Suit::Hearts => 'h',
+        };
+        write!(f, "{symbol}")
+    }
+}
@@ -1,15 +1,34 @@
 use crate::card::*;
 use rand::{RngCore, seq::SliceRandom};
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Deck {
     deck: Vec<Card>,
 }
 
+impl Default for Deck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Deck {
     pub fn draw_card(&mut self) -> Result<Card, CardError> {
         self.deck.pop().ok_or(CardError::EmptyDeck)
     }
 
+    /// Puts a card back on top of the deck, to be drawn next
+    pub fn put_back(&mut self, card: Card) {
+        self.deck.push(card);
+    }
+
+    /// Deck drawing `cards` in order
+    pub fn from_cards(mut cards: Vec<Card>) -> Self {
+        cards.reverse();
+        Self { deck: cards }
+    }
+
     pub fn shuffle(&mut self, rng: &mut impl RngCore) {
         self.deck.shuffle(rng)
     }
@@ -37,6 +56,6 @@ impl Deck {
             }
         }
 
-        Self { deck: deck }
+        Self { deck }
     }
 }
@@ -0,0 +1,99 @@
+use crate::card::*;
+use rand::{RngCore, distr::Distribution, distr::weighted::WeightedIndex, seq::SliceRandom};
+
+/// Cards of a full deck that are not in `known`
+pub fn remaining_cards<'a>(known: impl Iterator<Item = &'a Card>) -> Vec<Card> {
+    let mut used = [false; 52];
+    for card in known {
+        used[card.index()] = true;
+    }
+    (0..52)
+        .filter(|&i| !used[i])
+        .map(Card::from_index)
+        .collect()
+}
+
+/// Monte Carlo estimate of the share of the pot won by `hand` against `n_opponents` random hands
+pub fn equity_vs_random(
+    hand: &PlayerHand,
+    board: &[Card],
+    n_opponents: usize,
+    samples: usize,
+    rng: &mut impl RngCore,
+) -> f64 {
+    let mut remaining = remaining_cards(hand.cards().chain(board));
+    let missing = 5 - board.len();
+    let mut won = 0.0;
+
+    for _ in 0..samples {
+        let (drawn, _) = remaining.partial_shuffle(rng, 2 * n_opponents + missing);
+        let full_board: Vec<Card> = board
+            .iter()
+            .chain(&drawn[2 * n_opponents..])
+            .copied()
+            .collect();
+        let rank = HandRank::evaluate(hand.cards().chain(&full_board));
+
+        let mut ties = 1;
+        let mut beaten = false;
+        for opponent in drawn[..2 * n_opponents].chunks(2) {
+            let opponent_rank = HandRank::evaluate(opponent.iter().chain(&full_board));
+            if opponent_rank > rank {
+                beaten = true;
+                break;
+            } else if opponent_rank == rank {
+                ties += 1;
+            }
+        }
+        if !beaten {
+            won += 1.0 / ties as f64;
+        }
+    }
+
+    won / samples as f64
+}
+
+/// Monte Carlo estimate of the share of the pot won by `hand` against a single opponent whose
+/// hand is drawn from a weighted range
+///
+/// Hands of the range that conflict with known cards are ignored. Falls back to the equity
+/// against a random hand when nothing is left in the range.
+pub fn equity_vs_range(
+    hand: &PlayerHand,
+    board: &[Card],
+    range: &[(PlayerHand, f64)],
+    samples: usize,
+    rng: &mut impl RngCore,
+) -> f64 {
+    let range: Vec<&(PlayerHand, f64)> = range
+        .iter()
+        .filter(|(h, w)| *w > 0.0 && !h.conflicts(hand) && !board.iter().any(|c| h.contains(c)))
+        .collect();
+    let Ok(distribution) = WeightedIndex::new(range.iter().map(|(_, w)| *w)) else {
+        return equity_vs_random(hand, board, 1, samples, rng);
+    };
+
+    let mut remaining = remaining_cards(hand.cards().chain(board));
+    let missing = 5 - board.len();
+    let mut won = 0.0;
+
+    for _ in 0..samples {
+        let opponent = &range[distribution.sample(rng)].0;
+        let (drawn, _) = remaining.partial_shuffle(rng, missing + 2);
+        let full_board: Vec<Card> = board
+            .iter()
+            .chain(drawn.iter().filter(|c| !opponent.contains(c)).take(missing))
+            .copied()
+            .collect();
+
+        let rank = HandRank::evaluate(hand.cards().chain(&full_board));
+        let opponent_rank = HandRank::evaluate(opponent.cards().chain(&full_board));
+        won += match rank.cmp(&opponent_rank) {
+            std::cmp::Ordering::Greater => 1.0,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Less => 0.0,
+        };
+    }
+
+    won / samples as f64
+}
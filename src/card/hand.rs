@@ -1,16 +1,42 @@
 use crate::card::*;
 use itertools::Itertools;
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlayerHand {
     cards: [Card; 2],
 }
 
 impl PlayerHand {
-    pub fn cards<'a>(&'a self) -> impl Iterator<Item = &'a Card> {
+    pub fn new(first: Card, second: Card) -> Self {
+        Self {
+            cards: [first, second],
+        }
+    }
+
+    pub fn cards(&self) -> impl Iterator<Item = &Card> {
         self.cards.iter()
     }
+
+    pub fn contains(&self, card: &Card) -> bool {
+        self.cards.contains(card)
+    }
+
+    /// Every one of the 1326 distinct two card hands
+    pub fn all() -> Vec<PlayerHand> {
+        (0..52)
+            .tuple_combinations()
+            .map(|(a, b)| PlayerHand::new(Card::from_index(a), Card::from_index(b)))
+            .collect()
+    }
+
+    /// Whether the two hands share a card
+    pub fn conflicts(&self, other: &PlayerHand) -> bool {
+        other.cards().any(|c| self.contains(c))
+    }
 }
 
+/// Five cards, compared by their `HandRank`
 #[derive(Clone, Debug)]
 pub struct PokerHand {
     cards: [Card; 5],
@@ -26,181 +52,16 @@ impl PokerHand {
         })
     }
 
-    fn order_remaining_hand(
-        hand_start: impl Iterator<Item = Value>,
-        bin: &[usize; 13],
-    ) -> [Value; 5] {
-        hand_start
-            .chain(
-                bin.into_iter()
-                    .enumerate()
-                    .filter_map(|(i, count)| {
-                        if *count == 1 {
-                            Some(Value::from_index(i))
-                        } else {
-                            None
-                        }
-                    })
-                    .rev(),
-            )
-            .collect_array::<5>()
-            .unwrap()
-    }
-
-    pub fn get_hand_ordering(&self, hand_type: HandType) -> [Value; 5] {
-        let bins = indexed_bins(self.cards.iter().map(|c| &c.value));
-
-        match hand_type {
-            HandType::StraightFlush => PokerHand::order_remaining_hand(std::iter::empty(), &bins),
-            HandType::FourOfAKind => {
-                let index_value = bins
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, count)| **count == 4)
-                    .next()
-                    .unwrap()
-                    .0;
-
-                let value = Value::from_index(index_value);
-
-                PokerHand::order_remaining_hand(std::iter::repeat(value).take(4), &bins)
-            }
-            HandType::FullHouse => {
-                let value_index_3 = bins
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, count)| **count == 3)
-                    .next()
-                    .unwrap()
-                    .0;
-                let value_index_2 = bins
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, count)| **count == 2)
-                    .next()
-                    .unwrap()
-                    .0;
-
-                std::iter::repeat(Value::from_index(value_index_3))
-                    .take(3)
-                    .chain(std::iter::repeat(Value::from_index(value_index_2)).take(2))
-                    .collect_array::<5>()
-                    .unwrap()
-            }
-            HandType::Flush => PokerHand::order_remaining_hand(std::iter::empty(), &bins),
-            HandType::Straight => PokerHand::order_remaining_hand(std::iter::empty(), &bins),
-            HandType::ThreeOfAKind => {
-                let value_index = bins
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, count)| **count == 3)
-                    .next()
-                    .unwrap()
-                    .0;
-
-                let value = Value::from_index(value_index);
-
-                PokerHand::order_remaining_hand(std::iter::repeat(value).take(3), &bins)
-            }
-            HandType::DoublePair => {
-                let (value_low, value_high) = bins
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(i, count)| {
-                        if *count == 2 {
-                            Some(Value::from_index(i))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect_tuple()
-                    .unwrap();
-
-                PokerHand::order_remaining_hand(
-                    std::iter::repeat(value_high)
-                        .take(2)
-                        .chain(std::iter::repeat(value_low).take(2)),
-                    &bins,
-                )
-            }
-            HandType::Pair => {
-                let value_index = bins
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, count)| **count == 2)
-                    .next()
-                    .unwrap()
-                    .0;
-
-                let value = Value::from_index(value_index);
-
-                PokerHand::order_remaining_hand(std::iter::repeat(value).take(2), &bins)
-            }
-            HandType::HighCard => PokerHand::order_remaining_hand(std::iter::empty(), &bins),
-        }
+    pub fn cards(&self) -> impl Iterator<Item = &Card> {
+        self.cards.iter()
     }
 
-    fn best_hand_type(&self) -> HandType {
-        for s in (0..=8).rev() {
-            let hand_type = HandType::from_strength(s);
-            if self.contains_hand(hand_type.clone()) {
-                return hand_type;
-            }
-        }
-        unreachable!()
+    pub fn rank(&self) -> HandRank {
+        HandRank::evaluate(self.cards.iter())
     }
 
-    pub fn contains_hand(&self, hand_type: HandType) -> bool {
-        match hand_type {
-            HandType::StraightFlush => {
-                self.contains_hand(HandType::Flush) && self.contains_hand(HandType::Straight)
-            }
-            HandType::FourOfAKind => {
-                let bins = indexed_bins(self.cards.iter().map(|c| &c.value));
-                bins.into_iter().any(|count| count >= 4)
-            }
-            HandType::FullHouse => {
-                let bins = indexed_bins(self.cards.iter().map(|c| &c.value));
-                let contains_pair = bins.into_iter().any(|count| count == 2);
-                let contains_three_of_a_kind = bins.into_iter().any(|count| count == 3);
-                contains_pair && contains_three_of_a_kind
-            }
-            HandType::Flush => {
-                let bins = indexed_bins(self.cards.iter().map(|c| &c.suit));
-                bins.into_iter().any(|count| count == 5)
-            }
-            HandType::Straight => {
-                let bins = indexed_bins(self.cards.iter().map(|c| &c.suit));
-                let mut low_i = 12;
-                let mut high_i = 3;
-                let mut sum = bins.iter().cycle().skip(12).take(5).sum::<usize>();
-
-                while high_i < 13 {
-                    if sum == 5 {
-                        return true;
-                    }
-
-                    sum += bins[high_i] - bins[low_i];
-                    high_i += 1;
-                    low_i = (low_i + 1) % 13;
-                }
-
-                sum == 5
-            }
-            HandType::ThreeOfAKind => {
-                let bins = indexed_bins(self.cards.iter().map(|c| &c.value));
-                bins.into_iter().any(|count| count >= 3)
-            }
-            HandType::DoublePair => {
-                let bins = indexed_bins(self.cards.iter().map(|c| &c.value));
-                bins.into_iter().filter(|&count| count >= 2).count() == 2
-            }
-            HandType::Pair => {
-                let bins = indexed_bins(self.cards.iter().map(|c| &c.value));
-                bins.into_iter().any(|count| count >= 2)
-            }
-            HandType::HighCard => true,
-        }
+    pub fn hand_type(&self) -> HandType {
+        self.rank().hand_type()
     }
 }
 
@@ -223,59 +84,36 @@ fn card_ordering() {
         cards: [ace, two, three, four, five],
     };
 
-    assert!(straight_flush.contains_hand(HandType::Straight));
-    assert!(straight_flush.contains_hand(HandType::StraightFlush));
-    assert!(!straight_flush.contains_hand(HandType::Pair));
+    assert_eq!(straight_flush.hand_type(), HandType::StraightFlush);
 
     let straight = PokerHand {
         cards: [ace, two, three, four, five_unsuited],
     };
 
-    assert!(straight.contains_hand(HandType::Straight));
-    assert!(!straight.contains_hand(HandType::StraightFlush));
+    assert_eq!(straight.hand_type(), HandType::Straight);
 
     let royal_straight = PokerHand {
         cards: [ace, king_unsuited, jack, queen, ten],
     };
 
-    assert!(royal_straight.contains_hand(HandType::Straight));
+    assert_eq!(royal_straight.hand_type(), HandType::Straight);
 
     assert!(royal_straight > straight);
 }
 
 impl Ord for PokerHand {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+        self.rank().cmp(&other.rank())
     }
 }
 
 impl PartialOrd for PokerHand {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        let hand_type = self.best_hand_type();
-        let other_hand_type = other.best_hand_type();
-
-        if hand_type.strength() < other_hand_type.strength() {
-            return Some(std::cmp::Ordering::Less);
-        } else if hand_type.strength() > other_hand_type.strength() {
-            return Some(std::cmp::Ordering::Greater);
-        }
-
-        let ordered_values = self.get_hand_ordering(hand_type);
-        let other_ordered_values = other.get_hand_ordering(other_hand_type);
-
-        for i in 0..5 {
-            if ordered_values[i].number_value() < other_ordered_values[i].number_value() {
-                return Some(std::cmp::Ordering::Less);
-            } else if ordered_values[i].number_value() > other_ordered_values[i].number_value() {
-                return Some(std::cmp::Ordering::Greater);
-            }
-        }
-
-        Some(std::cmp::Ordering::Equal)
+        Some(self.cmp(other))
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum HandType {
     StraightFlush,
     FourOfAKind,
@@ -319,15 +157,119 @@ impl HandType {
     }
 }
 
+/// Comparable strength of the best five card hand that can be made from a set of cards
+///
+/// The hand type is stored in the high bits and the ordered values used to break ties
+/// in the low bits, so ranks compare like the hands they describe.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct HandRank(u32);
+
+impl HandRank {
+    pub fn evaluate<'a>(cards: impl Iterator<Item = &'a Card>) -> Self {
+        let mut bins = [0usize; 13];
+        let mut suit_masks = [0u16; 4];
+        for card in cards {
+            bins[card.value.index()] += 1;
+            suit_masks[card.suit.index()] |= 1 << card.value.index();
+        }
+        let value_mask = suit_masks
+            .iter()
+            .fold(0, |mask, suit_mask| mask | suit_mask);
+
+        if let Some(&flush_mask) = suit_masks.iter().find(|mask| mask.count_ones() >= 5) {
+            if let Some(high) = Self::straight_high(flush_mask) {
+                return Self::new(HandType::StraightFlush, &[high]);
+            }
+            let values: Vec<usize> = (0..13)
+                .rev()
+                .filter(|&i| flush_mask & (1 << i) != 0)
+                .collect();
+            return Self::new(HandType::Flush, &values[..5]);
+        }
+
+        // values grouped by count, highest count then highest value first
+        let mut groups: Vec<(usize, usize)> = (0..13)
+            .rev()
+            .filter(|&i| bins[i] > 0)
+            .map(|i| (bins[i], i))
+            .collect();
+        groups.sort_by(|a, b| b.cmp(a));
+
+        let kickers = |used: &[usize], n: usize| -> Vec<usize> {
+            (0..13)
+                .rev()
+                .filter(|i| bins[*i] > 0 && !used.contains(i))
+                .take(n)
+                .collect()
+        };
+
+        match groups.as_slice() {
+            [(4, quad), ..] => Self::new(
+                HandType::FourOfAKind,
+                &[&[*quad], &kickers(&[*quad], 1)[..]].concat(),
+            ),
+            [(3, trips), (pair_count, pair), ..] if *pair_count >= 2 => {
+                Self::new(HandType::FullHouse, &[*trips, *pair])
+            }
+            _ => {
+                if let Some(high) = Self::straight_high(value_mask) {
+                    return Self::new(HandType::Straight, &[high]);
+                }
+                match groups.as_slice() {
+                    [(3, trips), ..] => Self::new(
+                        HandType::ThreeOfAKind,
+                        &[&[*trips], &kickers(&[*trips], 2)[..]].concat(),
+                    ),
+                    [(2, high_pair), (2, low_pair), ..] => Self::new(
+                        HandType::DoublePair,
+                        &[
+                            &[*high_pair, *low_pair],
+                            &kickers(&[*high_pair, *low_pair], 1)[..],
+                        ]
+                        .concat(),
+                    ),
+                    [(2, pair), ..] => Self::new(
+                        HandType::Pair,
+                        &[&[*pair], &kickers(&[*pair], 3)[..]].concat(),
+                    ),
+                    _ => Self::new(HandType::HighCard, &kickers(&[], 5)),
+                }
+            }
+        }
+    }
+
+    fn new(hand_type: HandType, values: &[usize]) -> Self {
+        let mut rank = hand_type.strength() as u32;
+        for i in 0..5 {
+            rank = (rank << 4) | values.get(i).map_or(0, |&v| v as u32 + 1);
+        }
+        HandRank(rank)
+    }
+
+    /// Index of the highest card of the best straight contained in the mask, if any
+    fn straight_high(mask: u16) -> Option<usize> {
+        // bit 0 holds the ace playing low, bit i + 1 the value of index i
+        let extended = ((mask as u32) << 1) | ((mask as u32 >> 12) & 1);
+        (4..=13)
+            .rev()
+            .find(|high| (extended >> (high - 4)) & 0b11111 == 0b11111)
+            .map(|high| high - 1)
+    }
+
+    pub fn hand_type(&self) -> HandType {
+        HandType::from_strength((self.0 >> 20) as usize)
+    }
+}
+
 impl PartialEq for PokerHand {
     fn eq(&self, other: &Self) -> bool {
-        self.partial_cmp(other).unwrap() == std::cmp::Ordering::Equal
+        self.cmp(other) == std::cmp::Ordering::Equal
     }
 }
 
 impl Eq for PokerHand {}
 
-trait Indexed<const N: usize> {
+pub(crate) trait Indexed<const N: usize> {
     fn index(&self) -> usize;
     fn from_index(index: usize) -> Self;
 }
@@ -392,12 +334,55 @@ impl Indexed<13> for Value {
     }
 }
 
-fn indexed_bins<'a, const N: usize, T: Indexed<N> + 'a>(
-    items: impl Iterator<Item = &'a T>,
-) -> [usize; N] {
-    let mut bins = [0; N];
-    for e in items {
-        bins[e.index()] += 1;
-    }
-    bins
+#[test]
+fn hand_rank() {
+    let rank = |cards: &[(Suit, Value)]| {
+        HandRank::evaluate(
+            cards
+                .iter()
+                .map(|&(s, v)| Card::new(s, v))
+                .collect::<Vec<_>>()
+                .iter(),
+        )
+    };
+    use Suit::*;
+    use Value::*;
+
+    let wheel = rank(&[
+        (Clubs, Ace),
+        (Hearts, Two),
+        (Clubs, Three),
+        (Spades, Four),
+        (Clubs, Five),
+    ]);
+    let six_high = rank(&[
+        (Clubs, Six),
+        (Hearts, Two),
+        (Clubs, Three),
+        (Spades, Four),
+        (Clubs, Five),
+    ]);
+    assert_eq!(wheel.hand_type(), HandType::Straight);
+    assert!(wheel < six_high);
+
+    // two trips make a full house, the best five of seven cards are kept
+    let full_house = rank(&[
+        (Clubs, Nine),
+        (Hearts, Nine),
+        (Spades, Nine),
+        (Clubs, Four),
+        (Hearts, Four),
+        (Spades, Four),
+        (Diamonds, Ace),
+    ]);
+    assert_eq!(full_house.hand_type(), HandType::FullHouse);
+
+    let pair_with_gap = rank(&[
+        (Diamonds, Five),
+        (Diamonds, Two),
+        (Spades, Ace),
+        (Clubs, Five),
+        (Hearts, Three),
+    ]);
+    assert_eq!(pair_with_gap.hand_type(), HandType::Pair);
 }
@@ -1,12 +1,17 @@
 mod board;
+#[allow(clippy::module_inception)]
 mod card;
 mod deck;
+mod equity;
 mod hand;
+mod preflop;
 
 pub use board::*;
 pub use card::*;
 pub use deck::*;
+pub use equity::*;
 pub use hand::*;
+pub use preflop::*;
 
 use thiserror::Error;
 
@@ -18,4 +23,6 @@ pub enum CardError {
     InvalidPokerHandCardCount,
     #[error("Tried to draw from an empty deck")]
     EmptyDeck,
+    #[error("Invalid card {0}")]
+    InvalidCard(String),
 }
@@ -0,0 +1,174 @@
+use crate::card::*;
+use rand::RngCore;
+
+/// One of the 169 strategically distinct starting hands, such as `AKs`, `T9o` or `77`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct HandClass {
+    pub high: Value,
+    pub low: Value,
+    pub suited: bool,
+}
+
+impl HandClass {
+    pub const COUNT: usize = 169;
+
+    pub fn from_hand(hand: &PlayerHand) -> Self {
+        let (first, second) = {
+            let mut cards = hand.cards();
+            (*cards.next().unwrap(), *cards.next().unwrap())
+        };
+        let (high, low) = if first.value.index() >= second.value.index() {
+            (first.value, second.value)
+        } else {
+            (second.value, first.value)
+        };
+        HandClass {
+            high,
+            low,
+            suited: first.suit == second.suit,
+        }
+    }
+
+    /// Position in the usual 13x13 grid: aces first, suited hands above the diagonal and
+    /// offsuit hands below it
+    pub fn index(&self) -> usize {
+        let row = 12 - self.high.index();
+        let column = 12 - self.low.index();
+        if self.suited {
+            row * 13 + column
+        } else {
+            column * 13 + row
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        let row = index / 13;
+        let column = index % 13;
+        HandClass {
+            high: Value::from_index(12 - row.min(column)),
+            low: Value::from_index(12 - row.max(column)),
+            suited: row < column,
+        }
+    }
+
+    pub fn is_pair(&self) -> bool {
+        self.high == self.low
+    }
+
+    /// Number of two card hands in the class
+    pub fn combos(&self) -> usize {
+        if self.is_pair() {
+            6
+        } else if self.suited {
+            4
+        } else {
+            12
+        }
+    }
+
+    /// Every two card hand of the class
+    pub fn hands(&self) -> Vec<PlayerHand> {
+        let suits = [Suit::Spades, Suit::Diamonds, Suit::Clubs, Suit::Hearts];
+        let mut hands = Vec::with_capacity(self.combos());
+        for (i, &high_suit) in suits.iter().enumerate() {
+            for (j, &low_suit) in suits.iter().enumerate() {
+                let keep = if self.is_pair() {
+                    i < j
+                } else {
+                    (i == j) == self.suited
+                };
+                if keep {
+                    hands.push(PlayerHand::new(
+                        Card::new(high_suit, self.high),
+                        Card::new(low_suit, self.low),
+                    ));
+                }
+            }
+        }
+        hands
+    }
+
+    pub fn all() -> impl Iterator<Item = HandClass> {
+        (0..Self::COUNT).map(Self::from_index)
+    }
+}
+
+impl std::fmt::Display for HandClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_pair() {
+            write!(f, "{}{}", self.high, self.low)
+        } else {
+            let suffix = if self.suited { 's' } else { 'o' };
+            write!(f, "{}{}{}", self.high, self.low, suffix)
+        }
+    }
+}
+
+/// Equity of every hand class against one random hand, indexed by `HandClass::index`
+pub fn preflop_equities(samples: usize, rng: &mut impl RngCore) -> Vec<f64> {
+    HandClass::all()
+        .map(|class| equity_vs_random(&class.hands()[0], &[], 1, samples, rng))
+        .collect()
+}
+
+/// Equity of every hand class against every other, with the number of ways each pair of classes
+/// can be dealt without sharing a card
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PreflopEquityTable {
+    /// Share of the pot won by the first class, row-major over `HandClass::index`
+    equity: Vec<f64>,
+    combos: Vec<usize>,
+}
+
+impl PreflopEquityTable {
+    /// Estimates the equity of the first hand of every class against the whole of every other
+    /// class with `equity_vs_range`, equal classes splitting the pot
+    pub fn estimate(samples: usize, rng: &mut impl RngCore) -> Self {
+        let n = HandClass::COUNT;
+        let classes: Vec<HandClass> = HandClass::all().collect();
+        let hands: Vec<Vec<PlayerHand>> = classes.iter().map(HandClass::hands).collect();
+        let mut equity = vec![0.5; n * n];
+        let mut combos = vec![0; n * n];
+        for i in 0..n {
+            for j in 0..n {
+                combos[i * n + j] = hands[i]
+                    .iter()
+                    .flat_map(|hand| hands[j].iter().filter(|other| !other.conflicts(hand)))
+                    .count();
+                if i < j {
+                    let range: Vec<(PlayerHand, f64)> =
+                        hands[j].iter().map(|&hand| (hand, 1.0)).collect();
+                    let won = equity_vs_range(&hands[i][0], &[], &range, samples, rng);
+                    equity[i * n + j] = won;
+                    equity[j * n + i] = 1.0 - won;
+                }
+            }
+        }
+        PreflopEquityTable { equity, combos }
+    }
+
+    /// Share of the pot won by `class` against `other`
+    pub fn equity(&self, class: HandClass, other: HandClass) -> f64 {
+        self.equity[class.index() * HandClass::COUNT + other.index()]
+    }
+
+    /// Number of ways to deal `class` to one player and `other` to another
+    pub fn combos(&self, class: HandClass, other: HandClass) -> usize {
+        self.combos[class.index() * HandClass::COUNT + other.index()]
+    }
+}
+
+#[test]
+fn hand_class_grid() {
+    assert_eq!(HandClass::all().map(|c| c.combos()).sum::<usize>(), 1326);
+    for class in HandClass::all() {
+        assert_eq!(HandClass::from_index(class.index()), class);
+        for hand in class.hands() {
+            assert_eq!(HandClass::from_hand(&hand), class);
+        }
+    }
+    assert_eq!(HandClass::from_index(0).to_string(), "AA");
+    assert_eq!(HandClass::from_index(1).to_string(), "AKs");
+    assert_eq!(HandClass::from_index(13).to_string(), "AKo");
+}
@@ -0,0 +1,395 @@
+use crate::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Buy-in limits of a cash game, and the buy-ins made for the players before every hand
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CashRules {
+    pub min_buy_in: Chips,
+    pub max_buy_in: Chips,
+    /// Busted players buy in again for the maximum
+    pub auto_rebuy: bool,
+    /// Players below the maximum top up to it
+    pub auto_top_up: bool,
+}
+
+/// Player sitting at a seat of a cash game
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CashSeat {
+    /// Stack at the start of the current hand, before the blinds, or the current stack for a
+    /// player out of the hand
+    pub stack: Chips,
+    /// Not dealt in until sitting in again
+    pub sitting_out: bool,
+    /// Tops up to the maximum buy-in once the current hand ends
+    pub topping_up: bool,
+    /// Leaves the table once the current hand ends
+    pub leaving: bool,
+}
+
+/// Table where players come and go, buying chips as they please
+///
+/// Every hand is dealt to the players with chips who are not sitting out, the other seats being
+/// dealt out like busted seats, and the small blind moves to the next seat dealt in. Players
+/// join, leave, rebuy, top up and sit out between hands: changes to a player in the current
+/// hand wait for it to end, the others apply right away. Hands are only dealt while two players
+/// or more can play.
+#[derive(Clone, Debug)]
+pub struct CashGame {
+    settings: Settings,
+    rules: CashRules,
+    seats: Vec<Option<CashSeat>>,
+    /// Current hand, `None` while fewer than two players can play
+    game: Option<Game>,
+    /// Small blind of the last hand dealt
+    sb_seat: Option<usize>,
+    hands: usize,
+    /// Chips brought to every seat, by all the players who sat there
+    bought_in: Vec<Chips>,
+    /// Chips taken away from every seat by the players who left it
+    cashed_out: Vec<Chips>,
+    rng: StdRng,
+}
+
+impl CashGame {
+    /// Table with a player holding the initial stack at every seat
+    pub fn new(settings: Settings, rules: CashRules, seed: u64) -> Result<Self, GameError> {
+        settings.validate()?;
+        if rules.min_buy_in.is_zero() || rules.min_buy_in > rules.max_buy_in {
+            return Err(GameError::InvalidSettings);
+        }
+        let n = settings.n_players;
+        let seat = CashSeat {
+            stack: settings.initial_stack,
+            sitting_out: false,
+            topping_up: false,
+            leaving: false,
+        };
+        let mut cash_game = CashGame {
+            seats: vec![Some(seat); n],
+            bought_in: vec![settings.initial_stack; n],
+            cashed_out: vec![Chips::ZERO; n],
+            settings,
+            rules,
+            game: None,
+            sb_seat: None,
+            hands: 0,
+            rng: StdRng::seed_from_u64(seed),
+        };
+        cash_game.deal()?;
+        Ok(cash_game)
+    }
+
+    /// Game of the current hand
+    pub fn game(&self) -> Option<&Game> {
+        self.game.as_ref()
+    }
+
+    /// Plays an action in the current hand, dealing the next one once it is over
+    ///
+    /// Fails with `GameOver` while no hand is dealt.
+    pub fn play_turn(&mut self, action: Action) -> Result<(), GameError> {
+        let game = self.game.as_mut().ok_or(GameError::GameOver)?;
+        game.play_turn(action)?;
+        if game.hand_count() > 0 {
+            self.finish_hand()?;
+        }
+        Ok(())
+    }
+
+    /// Plays the current hand to its end with one agent per seat, then deals the next one
+    pub fn play_hand(&mut self, agents: &mut [Box<dyn Agent>]) -> Result<HandResult, GameError> {
+        let game = self.game.as_mut().ok_or(GameError::GameOver)?;
+        let result = play_hand(game, agents)?;
+        self.finish_hand()?;
+        Ok(result)
+    }
+
+    /// Hands played to the end
+    pub fn hand_count(&self) -> usize {
+        self.hands
+    }
+
+    /// Player of every seat, `None` for the empty seats
+    pub fn seats(&self) -> &[Option<CashSeat>] {
+        &self.seats
+    }
+
+    pub fn bought_in(&self) -> &[Chips] {
+        &self.bought_in
+    }
+
+    pub fn cashed_out(&self) -> &[Chips] {
+        &self.cashed_out
+    }
+
+    /// Seats a player with `buy_in` chips at an empty seat
+    pub fn join(&mut self, seat: usize, buy_in: Chips) -> Result<(), GameError> {
+        if self.seats.get(seat) != Some(&None) || !self.valid_buy_in(buy_in) {
+            return Err(GameError::InvalidSeatChange);
+        }
+        self.seats[seat] = Some(CashSeat {
+            stack: buy_in,
+            sitting_out: false,
+            topping_up: false,
+            leaving: false,
+        });
+        self.bought_in[seat] += buy_in;
+        self.deal_if_idle()
+    }
+
+    /// Takes the player of `seat` away with its chips, once the current hand ends
+    pub fn leave(&mut self, seat: usize) -> Result<(), GameError> {
+        self.player(seat)?.leaving = true;
+        if !self.in_hand(seat) {
+            self.remove(seat);
+        }
+        Ok(())
+    }
+
+    /// Buys `buy_in` chips for a busted player
+    pub fn rebuy(&mut self, seat: usize, buy_in: Chips) -> Result<(), GameError> {
+        let valid = self.valid_buy_in(buy_in);
+        let player = self.player(seat)?;
+        if !player.stack.is_zero() || !valid {
+            return Err(GameError::InvalidSeatChange);
+        }
+        player.stack = buy_in;
+        self.bought_in[seat] += buy_in;
+        self.deal_if_idle()
+    }
+
+    /// Brings the stack of the player of `seat` up to the maximum buy-in, once the current hand
+    /// ends
+    pub fn top_up(&mut self, seat: usize) -> Result<(), GameError> {
+        self.player(seat)?.topping_up = true;
+        if !self.in_hand(seat) {
+            self.buy_up_to_max(seat);
+            return self.deal_if_idle();
+        }
+        Ok(())
+    }
+
+    /// Deals the player of `seat` out from the next hand on
+    pub fn sit_out(&mut self, seat: usize) -> Result<(), GameError> {
+        self.player(seat)?.sitting_out = true;
+        Ok(())
+    }
+
+    /// Deals the player of `seat` in again from the next hand on
+    pub fn sit_in(&mut self, seat: usize) -> Result<(), GameError> {
+        self.player(seat)?.sitting_out = false;
+        self.deal_if_idle()
+    }
+
+    fn player(&mut self, seat: usize) -> Result<&mut CashSeat, GameError> {
+        self.seats
+            .get_mut(seat)
+            .and_then(Option::as_mut)
+            .ok_or(GameError::InvalidSeatChange)
+    }
+
+    fn valid_buy_in(&self, buy_in: Chips) -> bool {
+        (self.rules.min_buy_in..=self.rules.max_buy_in).contains(&buy_in)
+    }
+
+    /// Whether `seat` was dealt in the current hand
+    fn in_hand(&self, seat: usize) -> bool {
+        self.game
+            .as_ref()
+            .is_some_and(|game| game.hands()[seat].is_some())
+    }
+
+    fn remove(&mut self, seat: usize) {
+        if let Some(player) = self.seats[seat].take() {
+            self.cashed_out[seat] += player.stack;
+        }
+    }
+
+    fn buy_up_to_max(&mut self, seat: usize) {
+        let max_buy_in = self.rules.max_buy_in;
+        if let Some(player) = self.seats[seat].as_mut() {
+            let chips = max_buy_in.saturating_sub(player.stack);
+            player.stack += chips;
+            player.topping_up = false;
+            self.bought_in[seat] += chips;
+        }
+    }
+
+    fn deal_if_idle(&mut self) -> Result<(), GameError> {
+        match self.game {
+            Some(_) => Ok(()),
+            None => self.deal(),
+        }
+    }
+
+    /// Deals the next hand to the players who can play, finishing it right away when the blinds
+    /// put everyone all-in
+    fn deal(&mut self) -> Result<(), GameError> {
+        let n = self.seats.len();
+        let stacks: Vec<Chips> = self
+            .seats
+            .iter()
+            .map(|seat| match seat {
+                Some(player) if !player.sitting_out => player.stack,
+                _ => Chips::ZERO,
+            })
+            .collect();
+        if stacks.iter().filter(|stack| !stack.is_zero()).count() < 2 {
+            self.game = None;
+            return Ok(());
+        }
+        let last = self.sb_seat.unwrap_or(n - 1);
+        // two seats have chips, so one comes after the last small blind
+        let sb_seat = (1..=n)
+            .map(|i| (last + i) % n)
+            .find(|&seat| !stacks[seat].is_zero())
+            .unwrap();
+        self.sb_seat = Some(sb_seat);
+        let setup = HandSetup {
+            stacks,
+            sb_seat,
+            ..Default::default()
+        };
+        let game = Game::from_setup(self.settings.clone(), &setup, self.rng.random())?;
+        let over = game.hand_count() > 0;
+        self.game = Some(game);
+        if over {
+            self.finish_hand()?;
+        }
+        Ok(())
+    }
+
+    /// Applies the result of the hand and the changes waiting for it, then deals the next hand
+    fn finish_hand(&mut self) -> Result<(), GameError> {
+        let result = self
+            .game
+            .take()
+            .and_then(|game| game.last_hand_result().cloned())
+            .unwrap_or_default();
+        self.hands += 1;
+        for (seat, &delta) in result.deltas.iter().enumerate() {
+            if let Some(player) = self.seats[seat].as_mut() {
+                player.stack = player.stack.saturating_add_signed(delta);
+            }
+        }
+
+        for seat in 0..self.seats.len() {
+            let Some(player) = &self.seats[seat] else {
+                continue;
+            };
+            if player.leaving {
+                self.remove(seat);
+            } else if player.topping_up
+                || self.rules.auto_top_up
+                || (self.rules.auto_rebuy && player.stack.is_zero())
+            {
+                self.buy_up_to_max(seat);
+            }
+        }
+        self.deal()
+    }
+}
+
+#[test]
+fn play_cash_game() {
+    let settings = Settings {
+        n_players: 4,
+        initial_stack: Chips::new(200),
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        ..Default::default()
+    };
+    let rules = CashRules {
+        min_buy_in: Chips::new(100),
+        max_buy_in: Chips::new(200),
+        auto_rebuy: true,
+        auto_top_up: false,
+    };
+    let mut cash_game = CashGame::new(settings, rules, 4).unwrap();
+    let mut agents: Vec<Box<dyn Agent>> = vec![
+        Box::new(RaisingAgent),
+        Box::new(CallingAgent),
+        Box::new(TightAggressiveAgent::new(1)),
+        Box::new(PotOddsAgent::new(2)),
+    ];
+    let chips = |cash_game: &CashGame| {
+        let stacks: Chips = cash_game.seats().iter().flatten().map(|p| p.stack).sum();
+        stacks + cash_game.cashed_out().iter().sum::<Chips>()
+    };
+
+    // busted players buy in again, so no chips leave the table
+    for _ in 0..30 {
+        cash_game.play_hand(&mut agents).unwrap();
+        assert_eq!(
+            chips(&cash_game),
+            cash_game.bought_in().iter().sum::<Chips>()
+        );
+        assert!(
+            cash_game
+                .seats()
+                .iter()
+                .flatten()
+                .all(|p| !p.stack.is_zero())
+        );
+    }
+
+    // a player sitting out is dealt out and never posts a blind
+    cash_game.sit_out(3).unwrap();
+    cash_game.play_hand(&mut agents).unwrap();
+    for _ in 0..8 {
+        let state = cash_game.game().unwrap().get_observable_state(0);
+        assert!(state.seats[3].is_dead);
+        assert_ne!(state.sb_seat, 3);
+        cash_game.play_hand(&mut agents).unwrap();
+    }
+    cash_game.sit_in(3).unwrap();
+    cash_game.play_hand(&mut agents).unwrap();
+    assert!(cash_game.game().unwrap().hands()[3].is_some());
+
+    // a player leaves once its hand ends, and another one takes the seat
+    cash_game.leave(1).unwrap();
+    assert!(cash_game.seats()[1].is_some());
+    let stack = cash_game
+        .game()
+        .unwrap()
+        .get_observable_state(1)
+        .starting_stacks[1];
+    let cashed_out = cash_game.cashed_out()[1];
+    let result = cash_game.play_hand(&mut agents).unwrap();
+    assert!(cash_game.seats()[1].is_none());
+    assert_eq!(
+        cash_game.cashed_out()[1],
+        cashed_out + stack.saturating_add_signed(result.deltas[1])
+    );
+    assert!(matches!(
+        cash_game.join(1, Chips::new(50)),
+        Err(GameError::InvalidSeatChange)
+    ));
+    cash_game.join(1, Chips::new(150)).unwrap();
+    cash_game.top_up(1).unwrap();
+    cash_game.play_hand(&mut agents).unwrap();
+    assert_eq!(
+        cash_game.seats()[1].as_ref().unwrap().stack,
+        Chips::new(200)
+    );
+    assert_eq!(
+        chips(&cash_game),
+        cash_game.bought_in().iter().sum::<Chips>()
+    );
+
+    // no hand is dealt to a lone player
+    for seat in 1..4 {
+        cash_game.leave(seat).unwrap();
+    }
+    cash_game.play_hand(&mut agents).unwrap();
+    assert!(cash_game.game().is_none());
+    assert!(matches!(
+        cash_game.play_hand(&mut agents),
+        Err(GameError::GameOver)
+    ));
+    cash_game.join(2, Chips::new(100)).unwrap();
+    assert!(cash_game.game().is_some());
+}
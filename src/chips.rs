@@ -0,0 +1,260 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Sub, SubAssign};
+
+/// Amount of chips, the smallest unit the engine deals in
+///
+/// Arithmetic is checked: the operators panic instead of wrapping on overflow or when taking
+/// more chips than there are, the `checked_` methods return `None` instead.
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Chips(usize);
+
+impl Chips {
+    pub const ZERO: Chips = Chips(0);
+    pub const MAX: Chips = Chips(usize::MAX);
+
+    pub const fn new(amount: usize) -> Self {
+        Chips(amount)
+    }
+
+    pub const fn get(self) -> usize {
+        self.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn checked_add(self, other: Chips) -> Option<Chips> {
+        self.0.checked_add(other.0).map(Chips)
+    }
+
+    pub fn checked_sub(self, other: Chips) -> Option<Chips> {
+        self.0.checked_sub(other.0).map(Chips)
+    }
+
+    pub fn checked_mul(self, factor: usize) -> Option<Chips> {
+        self.0.checked_mul(factor).map(Chips)
+    }
+
+    pub fn checked_div(self, divisor: usize) -> Option<Chips> {
+        self.0.checked_div(divisor).map(Chips)
+    }
+
+    pub fn saturating_add(self, other: Chips) -> Chips {
+        Chips(self.0.saturating_add(other.0))
+    }
+
+    pub fn saturating_sub(self, other: Chips) -> Chips {
+        Chips(self.0.saturating_sub(other.0))
+    }
+
+    /// Chips left after gaining or losing `delta`, none rather than fewer
+    pub fn saturating_add_signed(self, delta: isize) -> Chips {
+        Chips(self.0.saturating_add_signed(delta))
+    }
+
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64
+    }
+
+    /// Chips gained or lost from `start` to `self`
+    pub fn delta(self, start: Chips) -> isize {
+        self.0 as isize - start.0 as isize
+    }
+}
+
+impl From<usize> for Chips {
+    fn from(amount: usize) -> Self {
+        Chips(amount)
+    }
+}
+
+impl From<Chips> for usize {
+    fn from(chips: Chips) -> Self {
+        chips.0
+    }
+}
+
+impl Add for Chips {
+    type Output = Chips;
+
+    fn add(self, other: Chips) -> Chips {
+        self.checked_add(other).expect("chip overflow")
+    }
+}
+
+impl Sub for Chips {
+    type Output = Chips;
+
+    fn sub(self, other: Chips) -> Chips {
+        self.checked_sub(other)
+            .expect("more chips taken than there are")
+    }
+}
+
+impl Mul<usize> for Chips {
+    type Output = Chips;
+
+    fn mul(self, factor: usize) -> Chips {
+        self.checked_mul(factor).expect("chip overflow")
+    }
+}
+
+impl Div<usize> for Chips {
+    type Output = Chips;
+
+    fn div(self, divisor: usize) -> Chips {
+        self.checked_div(divisor).expect("chips divided by zero")
+    }
+}
+
+impl Mul<Chips> for usize {
+    type Output = Chips;
+
+    fn mul(self, chips: Chips) -> Chips {
+        chips * self
+    }
+}
+
+impl AddAssign for Chips {
+    fn add_assign(&mut self, other: Chips) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Chips {
+    fn sub_assign(&mut self, other: Chips) {
+        *self = *self - other;
+    }
+}
+
+impl Sum for Chips {
+    fn sum<I: Iterator<Item = Chips>>(iter: I) -> Chips {
+        iter.fold(Chips::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Chips> for Chips {
+    fn sum<I: Iterator<Item = &'a Chips>>(iter: I) -> Chips {
+        iter.copied().sum()
+    }
+}
+
+impl fmt::Display for Chips {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// How amounts of chips are shown as money, the engine counting in chips all the same
+///
+/// With a symbol of `$` and two decimals a chip is a cent, and a big blind of 2 chips shows as
+/// `$0.02`. The default shows plain chip counts.
+#[derive(Default, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Denomination {
+    /// Written before the amounts, such as `$` or `€`
+    pub symbol: String,
+    /// Digits after the decimal point, a chip being worth `10^-decimals` of the currency, at
+    /// most 19 for `10^decimals` chips to be counted
+    pub decimals: u32,
+}
+
+impl Denomination {
+    /// Amount written with the symbol and the decimals of the denomination
+    pub fn format(&self, chips: impl Into<Chips>) -> String {
+        let chips = chips.into().get();
+        if self.decimals == 0 {
+            return format!("{}{chips}", self.symbol);
+        }
+        // no amount of chips makes a unit of the currency past the largest power of ten
+        let (units, rest) = match self.unit() {
+            Some(unit) => (chips / unit, chips % unit),
+            None => (0, chips),
+        };
+        format!(
+            "{}{units}.{rest:0width$}",
+            self.symbol,
+            width = self.decimals as usize
+        )
+    }
+
+    /// Chips in a unit of the currency, `None` when there are more than chips can count
+    pub fn unit(&self) -> Option<usize> {
+        10usize.checked_pow(self.decimals)
+    }
+
+    /// Chips of an amount written without the symbol, such as `0.25`, `None` unless it is a
+    /// whole number of chips
+    pub fn parse(&self, text: &str) -> Option<Chips> {
+        let (units, fraction) = text.split_once('.').unwrap_or((text, ""));
+        let digits = |text: &str| text.bytes().all(|byte| byte.is_ascii_digit());
+        if (units.is_empty() && fraction.is_empty()) || !digits(units) || !digits(fraction) {
+            return None;
+        }
+        let unit = self.unit()?;
+        let fraction = fraction.trim_end_matches('0');
+        let missing = (self.decimals as usize).checked_sub(fraction.len())?;
+        let units: usize = if units.is_empty() {
+            0
+        } else {
+            units.parse().ok()?
+        };
+        let fraction: usize = if fraction.is_empty() {
+            0
+        } else {
+            fraction.parse().ok()?
+        };
+        // below a unit, which fits
+        let fraction = fraction * 10usize.pow(missing as u32);
+        units
+            .checked_mul(unit)?
+            .checked_add(fraction)
+            .map(Chips::new)
+    }
+}
+
+#[test]
+fn chips() {
+    let stack = Chips::new(100);
+    assert_eq!(stack + Chips::new(20) - Chips::new(50), Chips::new(70));
+    assert_eq!(stack.checked_sub(Chips::new(101)), None);
+    assert_eq!(Chips::MAX.checked_add(Chips::new(1)), None);
+    assert_eq!(stack.saturating_sub(Chips::new(101)), Chips::ZERO);
+    assert_eq!(Chips::new(80).delta(stack), -20);
+    assert_eq!(stack.saturating_add_signed(-120), Chips::ZERO);
+    assert!(std::panic::catch_unwind(|| stack - Chips::new(101)).is_err());
+    assert_eq!(
+        [Chips::new(1), Chips::new(2)].iter().sum::<Chips>(),
+        Chips::new(3)
+    );
+
+    let dollars = Denomination {
+        symbol: "$".to_string(),
+        decimals: 2,
+    };
+    assert_eq!(dollars.format(2), "$0.02");
+    assert_eq!(dollars.format(Chips::new(12345)), "$123.45");
+    assert_eq!(Denomination::default().format(1500), "1500");
+    assert_eq!(dollars.parse("123.45"), Some(Chips::new(12345)));
+    assert_eq!(dollars.parse(".5"), Some(Chips::new(50)));
+    assert_eq!(dollars.parse("0.010"), Some(Chips::new(1)));
+    assert_eq!(dollars.parse("0.005"), None);
+    assert_eq!(dollars.parse("-1"), None);
+    assert_eq!(
+        Denomination::default().parse("1500"),
+        Some(Chips::new(1500))
+    );
+    let tiny = Denomination {
+        symbol: String::new(),
+        decimals: 21,
+    };
+    assert_eq!(tiny.unit(), None);
+    assert_eq!(tiny.format(5), "0.000000000000000000005");
+}
@@ -0,0 +1,169 @@
+use crate::*;
+
+const CARDS: usize = 52;
+const ROUNDS: usize = 4;
+/// Fold, check, call and raise
+const ACTION_KINDS: usize = 4;
+
+/// Turns what a seat observes into a fixed-size vector of floats, for neural networks
+///
+/// Seats are ordered from the observing seat onwards and chips are divided by the chips in
+/// play, so that the same situation is encoded the same way from any seat. The vector holds,
+/// in order:
+/// - the hole cards and the board cards, one-hot over the 52 cards each
+/// - the stacks, the bets of the round and the pot of the previous rounds
+/// - the position, one-hot over the distance from the small blind
+/// - the street, one-hot
+/// - the action history plane, one row per action starting with the oldest, each row holding
+///   the seat and the street one-hot, the kind of action one-hot and the raise amount
+///
+/// Only the last `max_history` actions fit in the plane, the rows left are zeros.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ObservationEncoder {
+    pub n_players: usize,
+    pub max_history: usize,
+}
+
+impl ObservationEncoder {
+    pub const DEFAULT_MAX_HISTORY: usize = 32;
+
+    pub fn new(n_players: usize) -> Self {
+        ObservationEncoder {
+            n_players,
+            max_history: Self::DEFAULT_MAX_HISTORY,
+        }
+    }
+
+    /// Length of the history rows
+    pub fn history_width(&self) -> usize {
+        self.n_players + ROUNDS + ACTION_KINDS + 1
+    }
+
+    /// Length of the encoded vectors
+    pub fn size(&self) -> usize {
+        2 * CARDS + 3 * self.n_players + 1 + ROUNDS + self.max_history * self.history_width()
+    }
+
+    /// Encoding of `state`, `InvalidSettings` when it is a state of a table of another size
+    pub fn encode(&self, state: &ObservableState) -> Result<Vec<f32>, GameError> {
+        let mut features = vec![0.0; self.size()];
+        self.encode_into(state, &mut features)?;
+        Ok(features)
+    }
+
+    /// Writes the encoding of `state` into `features`, `InvalidSettings` when `features` does
+    /// not hold `size` floats or the state is of a table of another size
+    pub fn encode_into(
+        &self,
+        state: &ObservableState,
+        features: &mut [f32],
+    ) -> Result<(), GameError> {
+        if features.len() != self.size() || state.seats.len() != self.n_players {
+            return Err(GameError::InvalidSettings);
+        }
+        features.fill(0.0);
+        let n = self.n_players;
+        let chips = state.starting_stacks.iter().sum::<Chips>().get().max(1) as f32;
+        let relative = |seat: usize| (seat + n - state.seat) % n;
+
+        let (hand, rest) = features.split_at_mut(CARDS);
+        for card in state.hand.iter().flat_map(PlayerHand::cards) {
+            hand[card.index()] = 1.0;
+        }
+        let (board, rest) = rest.split_at_mut(CARDS);
+        for card in &state.board {
+            board[card.index()] = 1.0;
+        }
+
+        let (stacks, rest) = rest.split_at_mut(n);
+        let (bets, rest) = rest.split_at_mut(n);
+        for (seat, info) in state.seats.iter().enumerate() {
+            stacks[relative(seat)] = info.stack.get() as f32 / chips;
+            bets[relative(seat)] = info.bet.get() as f32 / chips;
+        }
+        let (pot, rest) = rest.split_at_mut(1);
+        pot[0] = state.pot.get() as f32 / chips;
+
+        let (position, rest) = rest.split_at_mut(n);
+        position[(state.seat + n - state.sb_seat) % n] = 1.0;
+        let (street, history) = rest.split_at_mut(ROUNDS);
+        street[state.round as usize] = 1.0;
+
+        let skipped = state.history.len().saturating_sub(self.max_history);
+        for (record, row) in state.history[skipped..]
+            .iter()
+            .zip(history.chunks_exact_mut(self.history_width()))
+        {
+            let (seat, row) = row.split_at_mut(n);
+            seat[relative(record.seat)] = 1.0;
+            let (street, row) = row.split_at_mut(ROUNDS);
+            street[record.round as usize] = 1.0;
+            let (kind, amount) = row.split_at_mut(ACTION_KINDS);
+            match record.action {
+                Action::Fold => kind[0] = 1.0,
+                Action::Check => kind[1] = 1.0,
+                Action::Call => kind[2] = 1.0,
+                Action::Raise(raise) => {
+                    kind[3] = 1.0;
+                    amount[0] = raise.get() as f32 / chips;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn encode_observation() {
+    let settings = Settings {
+        n_players: 3,
+        initial_stack: Chips::new(100),
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        ..Default::default()
+    };
+    let mut game = Game::with_seed(settings, 4).unwrap();
+    game.play_turn(Action::Raise(Chips::new(20))).unwrap();
+    let state = game.get_observable_state(0);
+    let encoder = ObservationEncoder {
+        n_players: 3,
+        max_history: 1,
+    };
+    let features = encoder.encode(&state).unwrap();
+    assert_eq!(features.len(), encoder.size());
+
+    let hand: Vec<usize> = (0..52).filter(|&i| features[i] == 1.0).collect();
+    let mut expected: Vec<usize> = state.hand.unwrap().cards().map(Card::index).collect();
+    expected.sort();
+    assert_eq!(hand, expected);
+    assert!(features[52..104].iter().all(|&x| x == 0.0));
+
+    // seat 0 is the small blind, seat 2 raised to 30
+    let chips = |chips: f32| chips / 300.0;
+    assert_eq!(
+        &features[104..107],
+        &[chips(95.0), chips(90.0), chips(70.0)]
+    );
+    assert_eq!(&features[107..110], &[chips(5.0), chips(10.0), chips(30.0)]);
+    assert_eq!(features[110], 0.0);
+    assert_eq!(&features[111..114], &[1.0, 0.0, 0.0]);
+    assert_eq!(&features[114..118], &[1.0, 0.0, 0.0, 0.0]);
+    // the raise of seat 2 preflop, two seats after the observing one
+    assert_eq!(&features[118..121], &[0.0, 0.0, 1.0]);
+    assert_eq!(&features[121..125], &[1.0, 0.0, 0.0, 0.0]);
+    assert_eq!(&features[125..129], &[0.0, 0.0, 0.0, 1.0]);
+    assert_eq!(features[129], chips(20.0));
+    assert_eq!(features.len(), 130);
+
+    // the observing seat comes first
+    let features = encoder.encode(&game.get_observable_state(2)).unwrap();
+    assert_eq!(
+        &features[104..107],
+        &[chips(70.0), chips(95.0), chips(90.0)]
+    );
+    assert_eq!(&features[111..114], &[0.0, 0.0, 1.0]);
+
+    // a wrong size is an error rather than a panic
+    assert!(encoder.encode_into(&state, &mut [0.0; 10]).is_err());
+    assert!(ObservationEncoder::new(2).encode(&state).is_err());
+}
@@ -0,0 +1,140 @@
+use crate::*;
+
+/// Something that happened in a game, taken with `Game::take_events`
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameEvent {
+    /// A hand starts, with the stacks of every seat before the blinds
+    HandStarted {
+        /// Hands are numbered from 1 in every game
+        hand_number: usize,
+        sb_seat: usize,
+        stacks: Vec<Chips>,
+    },
+    /// Blinds actually posted, as seat and amount
+    BlindsPosted {
+        small_blind: (usize, Chips),
+        big_blind: (usize, Chips),
+    },
+    /// Hole cards dealt to a seat
+    CardsDealt { seat: usize, hand: PlayerHand },
+    ActionTaken {
+        seat: usize,
+        round: Round,
+        action: Action,
+        /// Chips put in the pot by the action
+        amount: Chips,
+    },
+    /// Board cards dealt for a new street
+    StreetDealt { round: Round, cards: Vec<Card> },
+    /// Hands revealed at the end of the hand, by seat
+    Showdown { hands: Vec<(usize, PlayerHand)> },
+    /// Chips a seat collected at the end of the hand, uncalled bets included
+    PotAwarded { seat: usize, amount: Chips },
+    /// A seat lost its last chip
+    PlayerEliminated { seat: usize },
+    /// Events of earlier hands nobody took, dropped when a new hand starts so that they do not
+    /// pile up, in place of which this comes first
+    EventsDropped { count: usize },
+}
+
+#[test]
+fn hand_events() {
+    let settings = Settings {
+        n_players: 3,
+        initial_stack: Chips::new(100),
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        ..Default::default()
+    };
+    let mut game = Game::with_seed(settings.clone(), 2).unwrap();
+    game.play_turn(Action::Raise(Chips::new(90))).unwrap();
+    game.play_turn(Action::Call).unwrap();
+    game.play_turn(Action::Fold).unwrap();
+
+    let events = game.take_events();
+    assert_eq!(
+        events[0],
+        GameEvent::HandStarted {
+            hand_number: 1,
+            sb_seat: 0,
+            stacks: vec![Chips::new(100); 3],
+        }
+    );
+    assert_eq!(
+        events[4],
+        GameEvent::BlindsPosted {
+            small_blind: (0, Chips::new(5)),
+            big_blind: (1, Chips::new(10)),
+        }
+    );
+    assert_eq!(
+        events[5],
+        GameEvent::ActionTaken {
+            seat: 2,
+            round: Round::PreFlop,
+            action: Action::Raise(Chips::new(90)),
+            amount: Chips::new(100),
+        }
+    );
+
+    let streets = events
+        .iter()
+        .filter(|event| matches!(event, GameEvent::StreetDealt { .. }))
+        .count();
+    assert_eq!(streets, 3);
+    let awarded: Chips = events
+        .iter()
+        .map(|event| match event {
+            GameEvent::PotAwarded { amount, .. } => *amount,
+            _ => Chips::ZERO,
+        })
+        .sum();
+    assert_eq!(awarded, Chips::new(210));
+    let Some(GameEvent::Showdown { hands }) = events
+        .iter()
+        .find(|event| matches!(event, GameEvent::Showdown { .. }))
+    else {
+        panic!("the all-in went to showdown");
+    };
+    assert_eq!(hands.len(), 2);
+    let eliminated = events
+        .iter()
+        .filter(|event| matches!(event, GameEvent::PlayerEliminated { .. }))
+        .count();
+    let dead = game
+        .get_observable_state(0)
+        .seats
+        .iter()
+        .filter(|seat| seat.is_dead)
+        .count();
+    assert_eq!(eliminated, dead);
+
+    // the next hand starts right away
+    assert!(matches!(
+        events.last(),
+        Some(GameEvent::BlindsPosted { .. })
+    ));
+    assert!(game.take_events().is_empty());
+
+    // the events of the hands before the last are counted when nobody takes them
+    let mut game = Game::with_seed(settings.clone(), 2).unwrap();
+    while game.hand_count() < 1 {
+        game.play_turn(Action::Fold).unwrap();
+    }
+    let first = game
+        .take_events()
+        .iter()
+        .position(|event| matches!(event, GameEvent::HandStarted { hand_number: 2, .. }))
+        .unwrap();
+    let mut untaken = Game::with_seed(settings, 2).unwrap();
+    while untaken.hand_count() < 2 {
+        untaken.play_turn(Action::Fold).unwrap();
+    }
+    let events = untaken.take_events();
+    assert_eq!(events[0], GameEvent::EventsDropped { count: first });
+    assert!(matches!(
+        events[1],
+        GameEvent::HandStarted { hand_number: 2, .. }
+    ));
+}
@@ -1,5 +1,10 @@
 use crate::card::*;
-use crate::*;
+use crate::chips::*;
+use crate::event::*;
+use crate::history::*;
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha12Rng;
 
 use thiserror::Error;
 
@@ -9,9 +14,24 @@ pub enum GameError {
     InvalidAction,
     #[error("Invalid round card count")]
     InvalidRoundCardCount,
+    #[error("The game is over")]
+    GameOver,
+    #[error("Hand setup is invalid")]
+    InvalidSetup,
+    #[error("Settings are invalid")]
+    InvalidSettings,
+    #[error("Bytes do not describe a game")]
+    InvalidBytes,
+    #[error("Seat change is not allowed")]
+    InvalidSeatChange,
+    #[error("Expected {expected} agents, got {got}")]
+    AgentCountMismatch { expected: usize, got: usize },
+    #[error(transparent)]
+    Card(#[from] CardError),
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Round {
     PreFlop,
     Flop,
@@ -20,7 +40,8 @@ pub enum Round {
 }
 
 impl Round {
-    fn n_cards(&self) -> usize {
+    /// Cards on the board during the round
+    pub fn n_cards(&self) -> usize {
         match self {
             Round::PreFlop => 0,
             Round::Flop => 3,
@@ -38,247 +59,1689 @@ impl Round {
             _ => Err(GameError::InvalidRoundCardCount),
         }
     }
+
+    pub fn next(&self) -> Option<Round> {
+        match self {
+            Round::PreFlop => Some(Round::Flop),
+            Round::Flop => Some(Round::Turn),
+            Round::Turn => Some(Round::River),
+            Round::River => None,
+        }
+    }
 }
 
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameState {
     current_seat: usize,
 
     board: Board,
+    deck: Deck,
 
     seats: Vec<Seat>,
+    hands: Vec<Option<PlayerHand>>,
     sb_seat: usize,
 
-    pot: usize,
+    pot: Chips,
+    /// Size of the last raise of the round, the minimum for the next one
+    last_raise: Chips,
+    /// Stacks at the start of the hand, before the blinds
+    starting_stacks: Vec<Chips>,
+    /// Actions taken since the start of the hand
+    history: Vec<ActionRecord>,
+    /// Changes made since the start of the hand, undone in reverse order
+    transitions: Vec<Transition>,
 
     round: Round,
 }
 
+/// State change recorded to be undone
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Transition {
+    /// Action of `seat`, with the seat and the last raise as they were before it
+    Action {
+        seat: usize,
+        before: Seat,
+        last_raise: Chips,
+    },
+    /// Street dealt, with the state of the round it closed
+    Street {
+        seats: Vec<Seat>,
+        pot: Chips,
+        last_raise: Chips,
+        current_seat: usize,
+        round: Round,
+    },
+    /// End of the previous hand, with what the next hand replaced
+    HandEnd(Box<EndedHand>),
+}
+
+impl Transition {
+    /// Whether an action is among `transitions` or the ones of the hand they follow
+    fn any_action(transitions: &[Transition]) -> bool {
+        transitions.iter().any(|transition| match transition {
+            Transition::Action { .. } => true,
+            Transition::HandEnd(ended) => Self::any_action(&ended.transitions),
+            Transition::Street { .. } => false,
+        })
+    }
+}
+
+/// What ending a hand and dealing the next one threw away
+///
+/// The stacks the hand started with, its cards and its actions are read back from its history,
+/// the last one the game keeps.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct EndedHand {
+    /// Seats as the last action left them, before the pot was awarded
+    seats: Vec<Seat>,
+    /// Cards not dealt in the hand
+    deck: Deck,
+    last_raise: Chips,
+    current_seat: usize,
+    transitions: Vec<Transition>,
+    /// Position of the generator before the next hand was shuffled
+    word_pos: u128,
+    /// Outcome of the hand before
+    last_hand_result: Option<HandResult>,
+    last_hand_history: Option<HandHistory>,
+}
+
 impl GameState {
-    fn next_valid_seat(&self, seat: usize) -> usize {
-        let mut next_seat = seat;
-        loop {
-            next_seat = (next_seat + 1) % self.seats.len();
-            if self.is_seat_valid(next_seat) {
-                break;
-            }
-        }
-        next_seat
+    /// Whether an action of this hand or of the previous one can be undone
+    fn can_undo(&self) -> bool {
+        Transition::any_action(&self.transitions)
     }
 
-    fn is_seat_valid(&self, seat: usize) -> bool {
-        self.seats[seat].is_valid()
+    /// Next seat that still has chips to play with, the seat itself if there is none
+    fn next_acting_seat(&self, seat: usize) -> usize {
+        self.next_seat_where(seat, Seat::can_act)
+    }
+
+    fn next_live_seat(&self, seat: usize) -> usize {
+        self.next_seat_where(seat, |s| !s.is_dead)
+    }
+
+    fn next_seat_where(&self, seat: usize, predicate: impl Fn(&Seat) -> bool) -> usize {
+        let n = self.seats.len();
+        (1..=n)
+            .map(|i| (seat + i) % n)
+            .find(|&s| predicate(&self.seats[s]))
+            .unwrap_or(seat)
+    }
+
+    fn players_in_hand(&self) -> usize {
+        self.seats.iter().filter(|seat| seat.is_valid()).count()
+    }
+
+    fn live_seats(&self) -> usize {
+        self.seats.iter().filter(|seat| !seat.is_dead).count()
+    }
+
+    /// First seat to act after the flop: the small blind, or the big blind heads-up
+    fn first_seat_after_flop(&self) -> usize {
+        let n = self.seats.len();
+        let start = if self.live_seats() == 2 {
+            self.next_live_seat(self.sb_seat)
+        } else {
+            self.sb_seat
+        };
+        self.next_acting_seat((start + n - 1) % n)
+    }
+
+    fn max_bet(&self) -> Chips {
+        self.seats
+            .iter()
+            .filter(|seat| seat.is_valid())
+            .map(|seat| seat.bet)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Chips committed by every seat since the start of the hand
+    fn committed(&self) -> Vec<Chips> {
+        self.starting_stacks
+            .iter()
+            .zip(&self.seats)
+            .map(|(&start, seat)| start - seat.stack)
+            .collect()
+    }
+
+    /// Chips in the pot that were called, the bet nobody matched aside
+    fn called_pot(&self) -> Chips {
+        let mut committed = self.committed();
+        committed.sort_unstable_by(|a, b| b.cmp(a));
+        let uncalled = committed[0] - committed.get(1).copied().unwrap_or_default();
+        committed.iter().sum::<Chips>() - uncalled
+    }
+
+    fn collect_bets(&mut self) {
+        for seat in self.seats.iter_mut() {
+            self.pot += seat.bet;
+            seat.bet = Chips::ZERO;
+            seat.last_action_in_current_round = None;
+        }
     }
 }
 
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameData {
     hand_cound: usize,
+    last_hand_result: Option<HandResult>,
+    last_hand_history: Option<HandHistory>,
+    /// Undone actions, the last one first to be replayed
+    redo: Vec<Action>,
+    /// Same generator as `StdRng`, which cannot be serialized
+    rng: ChaCha12Rng,
+}
+
+/// Outcome of a finished hand
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandResult {
+    /// Net chip change of every seat over the hand
+    pub deltas: Vec<isize>,
+    /// Whether the pot was decided by comparing hands
+    pub showdown: bool,
+    /// Cards on the board when the hand ended
+    pub board: Vec<Card>,
+    /// Hands revealed at showdown, by seat
+    pub shown_hands: Vec<(usize, PlayerHand)>,
+    /// Chips taken from the pot by the house, which the deltas account for
+    pub rake: Chips,
 }
 
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     settings: Settings,
     game_state: GameState,
     game_data: GameData,
+    /// Events not taken yet, left alone by `undo` and `restore`
+    events: Vec<GameEvent>,
 }
 
-#[derive(Clone, Copy)]
+/// Everything a game changes while it is played, its settings aside
+///
+/// Taken with `Game::snapshot` and brought back with `Game::restore`, for instance to rewind a
+/// game after a rollout. The deck and the generator are included, so a restored game deals the
+/// same cards again.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameSnapshot {
+    game_state: GameState,
+    game_data: GameData,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Seat {
-    pub stack: usize,
-    pub bet: usize,
+    pub stack: Chips,
+    pub bet: Chips,
     pub is_folded: bool,
     pub is_dead: bool,
     pub last_action_in_current_round: Option<Action>,
 }
 
 impl Seat {
-    fn new(stack: usize) -> Self {
+    fn new(stack: Chips) -> Self {
         Seat {
             stack,
-            bet: 0,
+            bet: Chips::ZERO,
             is_folded: false,
             is_dead: false,
             last_action_in_current_round: None,
         }
     }
 
-    fn is_valid(&self) -> bool {
+    pub fn is_valid(&self) -> bool {
         !self.is_dead && !self.is_folded
     }
+
+    /// In the hand and not all-in
+    pub fn can_act(&self) -> bool {
+        self.is_valid() && !self.stack.is_zero()
+    }
+
+    fn put_in(&mut self, amount: Chips) {
+        let amount = amount.min(self.stack);
+        self.stack -= amount;
+        self.bet += amount;
+    }
+}
+
+/// Starting point of a single hand, such as one read from a hand history
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandSetup {
+    /// Stacks before the blinds, zero for the seats out of the hand
+    pub stacks: Vec<Chips>,
+    pub sb_seat: usize,
+    /// Hole cards known in advance
+    pub hands: Vec<Option<PlayerHand>>,
+    /// Board cards known in advance, in the order they are dealt
+    pub board: Vec<Card>,
 }
 
-#[derive(Default, Clone)]
+/// Most seats a deck can deal to, two cards each and five on the board
+pub const MAX_PLAYERS: usize = 23;
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Settings {
     pub n_players: usize,
-    pub initial_stack: usize,
+    pub initial_stack: Chips,
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    pub betting: BettingStructure,
+    pub rake: Rake,
+    /// How chip amounts are shown in hand histories and interfaces
+    pub denomination: Denomination,
+}
+
+/// Chips the house takes from every pot, nothing by default
+///
+/// Only the chips that were called are raked, uncalled bets going back untouched.
+#[derive(Default, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rake {
+    /// Share of the pot taken, in hundredths of a percent
+    pub basis_points: usize,
+    /// Caps on the rake of a pot as `(min_players, cap)` pairs
+    ///
+    /// The cap applied is the one with the largest `min_players` not exceeding the players dealt
+    /// in, and the rake is uncapped when every `min_players` exceeds them.
+    pub caps: Vec<(usize, Chips)>,
+    /// Nothing is taken from the hands that end before the flop
+    pub no_flop_no_drop: bool,
+}
+
+impl Rake {
+    /// Rake of a pot of `pot` chips, in a hand dealt to `players` seats that saw a flop or not
+    pub fn amount(&self, pot: Chips, players: usize, flop: bool) -> Chips {
+        if self.no_flop_no_drop && !flop {
+            return Chips::ZERO;
+        }
+        let cap = self
+            .caps
+            .iter()
+            .filter(|(seats, _)| *seats <= players)
+            .max_by_key(|(seats, _)| *seats)
+            .map_or(Chips::MAX, |(_, cap)| *cap);
+        // taken from every 10 000 chips then from the rest, for the products not to overflow,
+        // and never more than the pot
+        let rest = (pot.get() % 10_000)
+            .checked_mul(self.basis_points)
+            .map(|rest| Chips::new(rest / 10_000));
+        let share = (pot / 10_000)
+            .checked_mul(self.basis_points)
+            .zip(rest)
+            .and_then(|(whole, rest)| whole.checked_add(rest))
+            .map_or(pot, |share| share.min(pot));
+        share.min(cap)
+    }
+}
+
+/// Limits put on the size of the raises
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BettingStructure {
+    #[default]
+    NoLimit,
+    /// Raises up to the pot after calling
+    PotLimit,
+    /// Bets and raises of exactly the small bet on the first two streets and of the big bet on
+    /// the last two
+    FixedLimit {
+        small_bet: Chips,
+        big_bet: Chips,
+        /// Bets and raises allowed per street, the big blind being the first bet preflop
+        max_bets: usize,
+    },
+}
+
+/// What bounds the raises of the seat to act
+struct RaiseLimits {
+    betting: BettingStructure,
+    round: Round,
+    /// Chips in the middle, including the bets of the current round
+    pot: Chips,
+    last_raise: Chips,
+    /// Bets and raises made in the round
+    bets: usize,
+}
+
+impl RaiseLimits {
+    fn new(
+        betting: BettingStructure,
+        round: Round,
+        pot: Chips,
+        last_raise: Chips,
+        history: &[ActionRecord],
+    ) -> Self {
+        RaiseLimits {
+            betting,
+            round,
+            pot,
+            last_raise,
+            bets: bets_in_round(history, round),
+        }
+    }
+
+    /// Smallest and largest raise allowed before looking at the stack, `None` once the street
+    /// is capped
+    fn bounds(&self, call_amount: Chips) -> Option<(Chips, Chips)> {
+        let min = self.last_raise.max(Chips::new(1));
+        match self.betting {
+            BettingStructure::NoLimit => Some((min, Chips::MAX)),
+            BettingStructure::PotLimit => Some((min, self.pot.saturating_add(call_amount))),
+            BettingStructure::FixedLimit {
+                small_bet,
+                big_bet,
+                max_bets,
+            } => {
+                let bet = match self.round {
+                    Round::PreFlop | Round::Flop => small_bet,
+                    Round::Turn | Round::River => big_bet,
+                };
+                (self.bets < max_bets).then_some((bet, bet))
+            }
+        }
+    }
+}
+
+/// Bets and raises made in `round`, the big blind counting as the first bet preflop
+fn bets_in_round(history: &[ActionRecord], round: Round) -> usize {
+    let raises = history
+        .iter()
+        .filter(|record| record.round == round && matches!(record.action, Action::Raise(_)))
+        .count();
+    raises + usize::from(round == Round::PreFlop)
+}
+
+impl Settings {
+    /// Checks that a game can be played: two to `MAX_PLAYERS` seats, chips to play with and
+    /// fewer than `Chips::MAX` on the table, a big blind at least as large as the small one,
+    /// fixed limit bets that allow betting and a rake below the pot
+    pub fn validate(&self) -> Result<(), GameError> {
+        if !(2..=MAX_PLAYERS).contains(&self.n_players)
+            || self.initial_stack.is_zero()
+            || self.initial_stack.checked_mul(self.n_players).is_none()
+            || self.big_blind.is_zero()
+            || self.small_blind > self.big_blind
+            || self.rake.basis_points > 10_000
+            || self.denomination.unit().is_none()
+            || matches!(
+                self.betting,
+                BettingStructure::FixedLimit { small_bet, big_bet, max_bets }
+                    if small_bet.is_zero() || big_bet.is_zero() || max_bets == 0
+            )
+        {
+            return Err(GameError::InvalidSettings);
+        }
+        Ok(())
+    }
 }
 
 impl Game {
-    pub fn new(settings: Settings) -> Game {
+    /// Game dealing its first hand, `GameError::InvalidSettings` when the settings do not
+    /// validate
+    pub fn new(settings: Settings) -> Result<Game, GameError> {
+        Self::with_seed(settings, rand::random())
+    }
+
+    /// Game shuffling its decks with a seeded generator, the same seed deals the same cards
+    pub fn with_seed(settings: Settings, seed: u64) -> Result<Game, GameError> {
+        settings.validate()?;
+        let mut game = Self::without_hand(settings, seed);
+        game.start_hand();
+        // blinds can put everyone all-in
+        game.settle()?;
+        Ok(game)
+    }
+
+    /// Game starting with the stacks, small blind and known cards of `setup`
+    ///
+    /// Cards left unknown are dealt at random from the rest of the deck.
+    pub fn from_setup(settings: Settings, setup: &HandSetup, seed: u64) -> Result<Game, GameError> {
+        settings.validate()?;
+        let n = settings.n_players;
+        let live = |seat: usize| !setup.stacks[seat].is_zero();
+        let total = setup
+            .stacks
+            .iter()
+            .try_fold(Chips::ZERO, |total, &stack| total.checked_add(stack));
+        if setup.stacks.len() != n
+            || total.is_none()
+            || setup.hands.len() > n
+            || setup.board.len() > 5
+            || setup.sb_seat >= n
+            || !live(setup.sb_seat)
+            || (0..n).filter(|&seat| live(seat)).count() < 2
+        {
+            return Err(GameError::InvalidSetup);
+        }
+
+        let known: Vec<Card> = setup
+            .hands
+            .iter()
+            .flatten()
+            .flat_map(|hand| hand.cards().copied().collect::<Vec<_>>())
+            .chain(setup.board.iter().copied())
+            .collect();
+        let mut rest = remaining_cards(known.iter());
+        if rest.len() + known.len() != 52 {
+            return Err(GameError::InvalidSetup);
+        }
+
+        let mut game = Self::without_hand(settings, seed);
+        rest.shuffle(&mut game.game_data.rng);
+        // cards in the order they are dealt: two per live seat, then the board
+        let mut cards = Vec::with_capacity(52);
+        for seat in (0..n).filter(|&seat| live(seat)) {
+            match setup.hands.get(seat).copied().flatten() {
+                Some(hand) => cards.extend(hand.cards().copied()),
+                // the deck holds enough cards for every seat
+                None => cards.extend([rest.pop().unwrap(), rest.pop().unwrap()]),
+            }
+        }
+        for i in 0..5 {
+            match setup.board.get(i) {
+                Some(&card) => cards.push(card),
+                None => cards.push(rest.pop().unwrap()),
+            }
+        }
+        cards.extend(rest);
+
+        for (seat, &stack) in game.game_state.seats.iter_mut().zip(&setup.stacks) {
+            seat.stack = stack;
+            seat.is_dead = stack.is_zero();
+        }
+        game.game_state.sb_seat = setup.sb_seat;
+        game.deal_hand(Deck::from_cards(cards));
+        game.settle()?;
+        Ok(game)
+    }
+
+    fn without_hand(settings: Settings, seed: u64) -> Game {
         Game {
             game_state: GameState {
                 current_seat: 0,
                 board: Board::new(),
+                deck: Deck::new(),
                 seats: vec![Seat::new(settings.initial_stack); settings.n_players],
+                hands: vec![None; settings.n_players],
                 sb_seat: 0,
-                pot: 0,
+                pot: Chips::ZERO,
+                last_raise: settings.big_blind,
+                starting_stacks: vec![settings.initial_stack; settings.n_players],
+                history: Vec::new(),
+                transitions: Vec::new(),
                 round: Round::PreFlop,
             },
             settings,
-            game_data: GameData { hand_cound: 0 },
+            game_data: GameData {
+                hand_cound: 0,
+                last_hand_result: None,
+                last_hand_history: None,
+                redo: Vec::new(),
+                rng: ChaCha12Rng::seed_from_u64(seed),
+            },
+            events: Vec::new(),
         }
     }
 
     pub fn play_turn(&mut self, action: Action) -> Result<(), GameError> {
-        self.handle_action(action);
-        self.state_logic();
-
+        self.apply_turn(action)?;
+        self.game_data.redo.clear();
         Ok(())
     }
 
+    fn apply_turn(&mut self, action: Action) -> Result<(), GameError> {
+        if self.over() {
+            return Err(GameError::GameOver);
+        }
+        self.handle_action(action)?;
+        self.state_logic()
+    }
+
     /// Go to next valid seat
     fn next_turn(&mut self) {
         self.game_state.current_seat = self
             .game_state
-            .next_valid_seat(self.game_state.current_seat);
+            .next_acting_seat(self.game_state.current_seat);
     }
 
-    fn update_round(&mut self) -> Result<(), GameError> {
-        let card_count = self.game_state.board.card_count();
-        self.game_state.round = Round::from_card_count(card_count)?;
+    fn update_round(game_state: &mut GameState) -> Result<(), GameError> {
+        let card_count = game_state.board.card_count();
+        game_state.round = Round::from_card_count(card_count)?;
         Ok(())
     }
 
     fn is_round_over(game_state: &GameState) -> bool {
-        if !game_state.seats.iter().any(|seat| seat.is_valid()) {
+        if game_state.players_in_hand() <= 1 {
             return true;
         }
 
-        // 1. everyone has played once
-        let everyone_has_played_once = game_state
+        // every seat that can still act has played once and matched the highest bet, unless
+        // nobody is left to play against
+        let max_bet = game_state.max_bet();
+        let acting_seats = game_state
             .seats
             .iter()
-            .all(|seat| !seat.is_valid() || seat.last_action_in_current_round.is_some());
-        if !everyone_has_played_once {
-            return false;
-        }
+            .filter(|seat| seat.can_act())
+            .count();
 
-        // 2. everyone (except all-in players) has the same bet (max of every valid player including
-        //    all-in players)
-        let max_bet = game_state
+        game_state
             .seats
             .iter()
-            .filter_map(|seat| {
-                if seat.is_valid() {
-                    Some(seat.bet)
-                } else {
-                    None
-                }
+            .filter(|seat| seat.can_act())
+            .all(|seat| {
+                seat.bet == max_bet
+                    && (seat.last_action_in_current_round.is_some() || acting_seats < 2)
             })
-            .max()
-            .unwrap();
-        let everyone_has_the_same_bet = game_state.seats.iter().all(|seat| seat.bet == max_bet);
+    }
+
+    fn next_round(&mut self) -> Result<(), GameError> {
+        let big_blind = self.settings.big_blind;
+        let game_state = &mut self.game_state;
+        game_state.transitions.push(Transition::Street {
+            seats: game_state.seats.clone(),
+            pot: game_state.pot,
+            last_raise: game_state.last_raise,
+            current_seat: game_state.current_seat,
+            round: game_state.round,
+        });
+        game_state.collect_bets();
+
+        let next_round = game_state
+            .round
+            .next()
+            .ok_or(GameError::InvalidRoundCardCount)?;
+        for _ in game_state.round.n_cards()..next_round.n_cards() {
+            let card = game_state.deck.draw_card()?;
+            game_state.board.add_card(card)?;
+        }
+        self.events.push(GameEvent::StreetDealt {
+            round: next_round,
+            cards: game_state
+                .board
+                .cards()
+                .skip(game_state.round.n_cards())
+                .copied()
+                .collect(),
+        });
+        Self::update_round(game_state)?;
 
-        everyone_has_the_same_bet
+        game_state.last_raise = big_blind;
+        game_state.current_seat = game_state.first_seat_after_flop();
+        Ok(())
     }
 
-    fn next_round(game_state: &mut GameState) {
-        todo!()
+    fn start_hand(&mut self) {
+        let mut deck = Deck::new();
+        deck.shuffle(&mut self.game_data.rng);
+        self.deal_hand(deck);
     }
 
-    fn next_hand(game_state: &mut GameState) {
+    fn deal_hand(&mut self, deck: Deck) {
+        self.emit(GameEvent::HandStarted {
+            hand_number: self.game_data.hand_cound + 1,
+            sb_seat: self.game_state.sb_seat,
+            stacks: self
+                .game_state
+                .seats
+                .iter()
+                .map(|seat| seat.stack)
+                .collect(),
+        });
+        let game_state = &mut self.game_state;
+
+        game_state.deck = deck;
+        game_state.board = Board::new();
+        game_state.round = Round::PreFlop;
+        game_state.pot = Chips::ZERO;
+        game_state.last_raise = self.settings.big_blind;
+        game_state.history.clear();
+
+        for (seat, hand) in game_state.seats.iter_mut().zip(game_state.hands.iter_mut()) {
+            seat.bet = Chips::ZERO;
+            seat.is_folded = false;
+            seat.last_action_in_current_round = None;
+            *hand = if seat.is_dead {
+                None
+            } else {
+                // a full deck holds enough cards for the `MAX_PLAYERS` seats of a valid game
+                let first = game_state.deck.draw_card().unwrap();
+                let second = game_state.deck.draw_card().unwrap();
+                Some(PlayerHand::new(first, second))
+            };
+        }
+        for (seat, hand) in game_state.hands.iter().enumerate() {
+            if let Some(hand) = *hand {
+                self.events.push(GameEvent::CardsDealt { seat, hand });
+            }
+        }
+        game_state.starting_stacks = game_state.seats.iter().map(|seat| seat.stack).collect();
+
+        let sb_seat = game_state.sb_seat;
+        let bb_seat = game_state.next_live_seat(sb_seat);
+        game_state.seats[sb_seat].put_in(self.settings.small_blind);
+        game_state.seats[bb_seat].put_in(self.settings.big_blind);
+        self.events.push(GameEvent::BlindsPosted {
+            small_blind: (sb_seat, game_state.seats[sb_seat].bet),
+            big_blind: (bb_seat, game_state.seats[bb_seat].bet),
+        });
+
+        game_state.current_seat = game_state.next_acting_seat(bb_seat);
+    }
+
+    fn next_hand(&mut self) -> Result<(), GameError> {
+        // only the last hand is kept to be undone
+        let mut transitions = std::mem::take(&mut self.game_state.transitions);
+        if let Some(Transition::HandEnd(_)) = transitions.first() {
+            transitions.remove(0);
+        }
+        let ended = EndedHand {
+            seats: self.game_state.seats.clone(),
+            deck: self.game_state.deck.clone(),
+            last_raise: self.game_state.last_raise,
+            current_seat: self.game_state.current_seat,
+            transitions,
+            word_pos: self.game_data.rng.get_word_pos(),
+            last_hand_result: self.game_data.last_hand_result.take(),
+            last_hand_history: self.game_data.last_hand_history.take(),
+        };
+        self.game_state.transitions = vec![Transition::HandEnd(Box::new(ended))];
+
+        let game_state = &mut self.game_state;
+
         // end current hand:
+        //   - take the rake
         //   - check for winner(s)
         //   - update winner(s) stack(s)
         //   - set dead flags
+        game_state.collect_bets();
+        let showdown = game_state.players_in_hand() > 1;
+        let rake = self.settings.rake.amount(
+            game_state.called_pot(),
+            game_state.live_seats(),
+            game_state.board.card_count() >= Round::Flop.n_cards(),
+        );
+        let payouts = Self::payouts(game_state, rake)?;
+        for (seat, &payout) in game_state.seats.iter_mut().zip(&payouts) {
+            seat.stack += payout;
+        }
+        game_state.pot = Chips::ZERO;
+
+        self.game_data.last_hand_result = Some(HandResult {
+            deltas: game_state
+                .seats
+                .iter()
+                .zip(&game_state.starting_stacks)
+                .map(|(seat, &start)| seat.stack.delta(start))
+                .collect(),
+            showdown,
+            board: game_state.board.cards().copied().collect(),
+            shown_hands: if showdown {
+                game_state
+                    .seats
+                    .iter()
+                    .zip(&game_state.hands)
+                    .enumerate()
+                    .filter(|(_, (seat, _))| seat.is_valid())
+                    .filter_map(|(i, (_, hand))| hand.map(|hand| (i, hand)))
+                    .collect()
+            } else {
+                Vec::new()
+            },
+            rake,
+        });
+        self.game_data.last_hand_history = Some(HandHistory::new(
+            self.game_data.hand_cound + 1,
+            &self.settings,
+            game_state.sb_seat,
+            game_state.next_live_seat(game_state.sb_seat),
+            &game_state.starting_stacks,
+            &game_state.hands,
+            &game_state.history,
+            game_state.board.cards().copied().collect(),
+            self.game_data.last_hand_result.as_ref().unwrap(),
+            payouts.clone(),
+        ));
+        self.game_data.hand_cound += 1;
+
+        let result = self.game_data.last_hand_result.as_ref().unwrap();
+        if showdown {
+            self.events.push(GameEvent::Showdown {
+                hands: result.shown_hands.clone(),
+            });
+        }
+        for (seat, &amount) in payouts.iter().enumerate() {
+            if !amount.is_zero() {
+                self.events.push(GameEvent::PotAwarded { seat, amount });
+            }
+        }
+        for (seat, state) in game_state.seats.iter_mut().enumerate() {
+            if !state.is_dead && state.stack.is_zero() {
+                self.events.push(GameEvent::PlayerEliminated { seat });
+            }
+            state.is_dead = state.stack.is_zero();
+        }
+        if game_state.live_seats() < 2 {
+            return Ok(());
+        }
 
         // next_hand:
-        //   - update round
-        game_state.round = Round::PreFlop;
         //   - change sb
-        game_state.sb_seat = game_state.next_valid_seat(game_state.sb_seat);
-        //   - change current player
-        game_state.current_seat = game_state.sb_seat;
-        //   - deal new hand
-        todo!()
+        game_state.sb_seat = game_state.next_live_seat(game_state.sb_seat);
+        //   - reset round, deal new hand and change current player
+        self.start_hand();
+        Ok(())
     }
 
-    fn is_hand_over(&self) -> bool {
-        Self::is_round_over(&self.game_state) && self.game_state.round == Round::River
-    }
+    /// Chips won by every seat, splitting the pot into side pots by commitment level
+    ///
+    /// The rake is taken from the main pot first, then from the side pots.
+    fn payouts(game_state: &GameState, rake: Chips) -> Result<Vec<Chips>, GameError> {
+        let n = game_state.seats.len();
+        let committed = game_state.committed();
+        let mut payouts = vec![Chips::ZERO; n];
 
-    fn state_logic(&mut self) {
-        if self.is_hand_over() {
-            Self::next_hand(&mut self.game_state);
-            return;
+        let in_hand: Vec<usize> = (0..n).filter(|&i| game_state.seats[i].is_valid()).collect();
+        if in_hand.len() == 1 {
+            payouts[in_hand[0]] = committed.iter().sum::<Chips>() - rake;
+            return Ok(payouts);
         }
+        let mut rake = rake;
 
-        if Self::is_round_over(&self.game_state) {
-            Self::next_round(&mut self.game_state);
-            return;
+        let mut ranks = vec![None; n];
+        for &i in &in_hand {
+            let hand = game_state.hands[i].ok_or(GameError::InvalidAction)?;
+            ranks[i] = Some(game_state.board.hand_rank(&hand));
         }
 
-        self.next_turn();
+        let mut levels: Vec<Chips> = in_hand.iter().map(|&i| committed[i]).collect();
+        levels.sort();
+        levels.dedup();
 
-        todo!()
+        let mut previous_level = Chips::ZERO;
+        for (l, &level) in levels.iter().enumerate() {
+            // the last pot also returns whatever nobody could match
+            let cap = if l + 1 == levels.len() {
+                Chips::MAX
+            } else {
+                level
+            };
+            let mut amount: Chips = committed
+                .iter()
+                .map(|&c| c.min(cap) - c.min(previous_level))
+                .sum();
+            let raked = rake.min(amount);
+            amount -= raked;
+            rake -= raked;
+
+            let eligible = in_hand.iter().filter(|&&i| committed[i] >= level);
+            let best = eligible.clone().filter_map(|&i| ranks[i]).max();
+            // odd chips go to the first winners from the small blind on
+            let winners: Vec<usize> = (0..n)
+                .map(|i| (game_state.sb_seat + i) % n)
+                .filter(|i| eligible.clone().any(|e| e == i) && ranks[*i] == best)
+                .collect();
+            let odd_chips = amount.get() % winners.len();
+            for (w, &winner) in winners.iter().enumerate() {
+                payouts[winner] += amount / winners.len() + Chips::new(usize::from(w < odd_chips));
+            }
+            previous_level = level;
+        }
+
+        Ok(payouts)
+    }
+
+    fn is_hand_over(&self) -> bool {
+        self.game_state.players_in_hand() <= 1
+            || (Self::is_round_over(&self.game_state) && self.game_state.round == Round::River)
     }
 
-    fn handle_action(&mut self, action: Action) {
-        // todo: check action validity
+    fn state_logic(&mut self) -> Result<(), GameError> {
+        if !Self::is_round_over(&self.game_state) {
+            self.next_turn();
+            return Ok(());
+        }
+
+        self.settle()
+    }
+
+    /// Move on through rounds and hands until a seat has to act
+    fn settle(&mut self) -> Result<(), GameError> {
+        while !self.over() && Self::is_round_over(&self.game_state) {
+            if self.is_hand_over() {
+                self.next_hand()?;
+            } else {
+                self.next_round()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_action(&mut self, action: Action) -> Result<(), GameError> {
+        let legal_actions = self.legal_actions();
+        if !legal_actions.contains(action) {
+            return Err(GameError::InvalidAction);
+        }
+
+        let game_state = &mut self.game_state;
+        let previous_raise = game_state.last_raise;
+        let seat = &mut game_state.seats[game_state.current_seat];
+        let before = *seat;
         match action {
-            Action::Fold => self.game_state.seats[self.game_state.current_seat].is_folded = true,
+            Action::Fold => seat.is_folded = true,
             Action::Raise(amount) => {
-                self.game_state.seats[self.game_state.current_seat].bet += amount
+                seat.put_in(legal_actions.call_amount + amount);
+                game_state.last_raise = game_state.last_raise.max(amount);
             }
-            Action::Call => todo!(),
+            Action::Call => seat.put_in(legal_actions.call_amount),
             Action::Check => {}
         }
+        game_state.transitions.push(Transition::Action {
+            seat: game_state.current_seat,
+            before,
+            last_raise: previous_raise,
+        });
+        seat.last_action_in_current_round = Some(action);
+        self.events.push(GameEvent::ActionTaken {
+            seat: game_state.current_seat,
+            round: game_state.round,
+            action,
+            amount: seat.bet - before.bet,
+        });
+        game_state.history.push(ActionRecord {
+            seat: game_state.current_seat,
+            round: game_state.round,
+            action,
+        });
+        Ok(())
     }
 
     pub fn current_seat(&self) -> usize {
         self.game_state.current_seat
     }
 
-    fn get_observable_state(&self) -> ObservableState {
-        self.into()
+    pub fn legal_actions(&self) -> LegalActions {
+        let game_state = &self.game_state;
+        let limits = RaiseLimits::new(
+            self.settings.betting,
+            game_state.round,
+            game_state.pot + game_state.seats.iter().map(|seat| seat.bet).sum::<Chips>(),
+            game_state.last_raise,
+            &game_state.history,
+        );
+        LegalActions::new(&game_state.seats, game_state.current_seat, &limits)
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Full record of the last finished hand
+    pub fn last_hand_history(&self) -> Option<&HandHistory> {
+        self.game_data.last_hand_history.as_ref()
+    }
+
+    /// Number of hands played to the end
+    pub fn hand_count(&self) -> usize {
+        self.game_data.hand_cound
+    }
+
+    pub fn last_hand_result(&self) -> Option<&HandResult> {
+        self.game_data.last_hand_result.as_ref()
+    }
+
+    /// Cards of every seat, `None` for the seats out of the game
+    pub fn hands(&self) -> &[Option<PlayerHand>] {
+        &self.game_state.hands
+    }
+
+    pub fn get_observable_state(&self, seat: usize) -> ObservableState {
+        let game_state = &self.game_state;
+        ObservableState {
+            seat,
+            hand: game_state.hands[seat],
+            board: game_state.board.cards().copied().collect(),
+            seats: game_state.seats.clone(),
+            current_seat: game_state.current_seat,
+            sb_seat: game_state.sb_seat,
+            pot: game_state.pot,
+            round: game_state.round,
+            last_raise: game_state.last_raise,
+            small_blind: self.settings.small_blind,
+            big_blind: self.settings.big_blind,
+            betting: self.settings.betting,
+            starting_stacks: game_state.starting_stacks.clone(),
+            history: game_state.history.clone(),
+        }
     }
 
     pub fn over(&self) -> bool {
-        false
+        self.game_state.live_seats() < 2
+    }
+
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            game_state: self.game_state.clone(),
+            game_data: self.game_data.clone(),
+        }
+    }
+
+    /// Puts the game back where it was when `snapshot` was taken
+    pub fn restore(&mut self, snapshot: &GameSnapshot) {
+        self.game_state.clone_from(&snapshot.game_state);
+        self.game_data.clone_from(&snapshot.game_data);
+    }
+
+    /// Events since the last call
+    ///
+    /// Only the events of the last two hands are kept, the older ones being replaced by a single
+    /// `GameEvent::EventsDropped` counting them, so take the events at least once a hand to see
+    /// them all.
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
     }
+
+    fn emit(&mut self, event: GameEvent) {
+        if let GameEvent::HandStarted { .. } = event
+            && let Some(start) = self
+                .events
+                .iter()
+                .rposition(|event| matches!(event, GameEvent::HandStarted { .. }))
+            && start > 0
+        {
+            let count = self
+                .events
+                .drain(..start)
+                .map(|event| match event {
+                    GameEvent::EventsDropped { count } => count,
+                    _ => 1,
+                })
+                .sum();
+            self.events.insert(0, GameEvent::EventsDropped { count });
+        }
+        self.events.push(event);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.game_state.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.game_data.redo.is_empty()
+    }
+
+    /// Takes back the last action, along with the streets and the end of hand it led to
+    ///
+    /// Actions can be undone back to the start of the previous hand. Returns the undone action,
+    /// which `redo` plays again.
+    pub fn undo(&mut self) -> Option<Action> {
+        if !self.can_undo() {
+            return None;
+        }
+        let mut redo = std::mem::take(&mut self.game_data.redo);
+        let action = loop {
+            let game_state = &mut self.game_state;
+            match game_state.transitions.pop()? {
+                Transition::Action {
+                    seat,
+                    before,
+                    last_raise,
+                } => {
+                    game_state.seats[seat] = before;
+                    game_state.last_raise = last_raise;
+                    game_state.current_seat = seat;
+                    break game_state.history.pop()?.action;
+                }
+                Transition::Street {
+                    seats,
+                    pot,
+                    last_raise,
+                    current_seat,
+                    round,
+                } => {
+                    // cards go back on top of the deck, to be dealt again by the same street
+                    while game_state.board.card_count() > round.n_cards() {
+                        let card = game_state.board.remove_card()?;
+                        game_state.deck.put_back(card);
+                    }
+                    game_state.seats = seats;
+                    game_state.pot = pot;
+                    game_state.last_raise = last_raise;
+                    game_state.current_seat = current_seat;
+                    game_state.round = round;
+                }
+                Transition::HandEnd(ended) => self.reopen_hand(*ended)?,
+            }
+        };
+        redo.push(action);
+        self.game_data.redo = redo;
+        Some(action)
+    }
+
+    /// Brings back the state of the last hand as its last action left it
+    fn reopen_hand(&mut self, ended: EndedHand) -> Option<()> {
+        let history = self.game_data.last_hand_history.take()?;
+        let game_state = &mut self.game_state;
+        let mut board = Board::new();
+        for &card in &history.board {
+            board.add_card(card).ok()?;
+        }
+        game_state.round = Round::from_card_count(board.card_count()).ok()?;
+        game_state.board = board;
+        game_state.deck = ended.deck;
+        game_state.hands = history.hands;
+        game_state.sb_seat = history.blinds.first()?.0;
+        game_state.history = history
+            .actions
+            .iter()
+            .map(|action| ActionRecord {
+                seat: action.seat,
+                round: action.round,
+                action: action.action,
+            })
+            .collect();
+        // bets of the last round had not been collected yet
+        game_state.pot = ended
+            .seats
+            .iter()
+            .zip(&history.stacks)
+            .map(|(seat, &start)| start - seat.stack - seat.bet)
+            .sum();
+        game_state.starting_stacks = history.stacks;
+        game_state.seats = ended.seats;
+        game_state.last_raise = ended.last_raise;
+        game_state.current_seat = ended.current_seat;
+        game_state.transitions = ended.transitions;
+
+        let game_data = &mut self.game_data;
+        game_data.hand_cound -= 1;
+        game_data.last_hand_result = ended.last_hand_result;
+        game_data.last_hand_history = ended.last_hand_history;
+        game_data.rng.set_word_pos(ended.word_pos);
+        Some(())
+    }
+
+    /// Plays the last undone action again
+    pub fn redo(&mut self) -> Option<Action> {
+        let action = self.game_data.redo.pop()?;
+        let redo = std::mem::take(&mut self.game_data.redo);
+        // the action was legal when it was undone, and the same cards are dealt again
+        self.apply_turn(action).ok()?;
+        self.game_data.redo = redo;
+        Some(action)
+    }
+}
+
+/// Version of the format written by `Game::to_bytes`, to bump whenever a game changes shape
+#[cfg(feature = "serde")]
+const BYTES_VERSION: u32 = 7;
+
+#[cfg(feature = "serde")]
+impl Game {
+    /// Whole game as bytes, the generator included, for `from_bytes` to read back
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = BYTES_VERSION.to_le_bytes().to_vec();
+        // writing to memory cannot fail
+        bincode::serialize_into(&mut bytes, self).unwrap();
+        bytes
+    }
+
+    /// Game written by `to_bytes` with the same version of the format
+    pub fn from_bytes(bytes: &[u8]) -> Result<Game, GameError> {
+        match bytes.split_first_chunk() {
+            Some((version, game)) if u32::from_le_bytes(*version) == BYTES_VERSION => {
+                bincode::deserialize(game).map_err(|_| GameError::InvalidBytes)
+            }
+            _ => Err(GameError::InvalidBytes),
+        }
+    }
+}
+
+/// What one seat can see of the game
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ObservableState {
+    /// Seat the game is observed from
+    pub seat: usize,
+    pub hand: Option<PlayerHand>,
+    pub board: Vec<Card>,
+    pub seats: Vec<Seat>,
+    pub current_seat: usize,
+    pub sb_seat: usize,
+    /// Chips collected from the previous rounds
+    pub pot: Chips,
+    pub round: Round,
+    pub last_raise: Chips,
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    pub betting: BettingStructure,
+    /// Stacks at the start of the hand, before the blinds
+    pub starting_stacks: Vec<Chips>,
+    pub history: Vec<ActionRecord>,
+}
+
+impl ObservableState {
+    /// Chips in the middle, including the bets of the current round
+    pub fn total_pot(&self) -> Chips {
+        self.pot + self.seats.iter().map(|seat| seat.bet).sum::<Chips>()
+    }
+
+    pub fn legal_actions(&self) -> LegalActions {
+        let limits = RaiseLimits::new(
+            self.betting,
+            self.round,
+            self.total_pot(),
+            self.last_raise,
+            &self.history,
+        );
+        LegalActions::new(&self.seats, self.current_seat, &limits)
+    }
+}
+
+/// Actions available to the seat to act
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LegalActions {
+    /// Chips needed to call, zero when checking is possible
+    pub call_amount: Chips,
+    /// Smallest and largest amount a raise can add on top of the call
+    pub raise_range: Option<(Chips, Chips)>,
 }
 
-struct ObservableState {}
+impl LegalActions {
+    fn new(seats: &[Seat], seat: usize, limits: &RaiseLimits) -> Self {
+        let max_bet = seats
+            .iter()
+            .filter(|s| s.is_valid())
+            .map(|s| s.bet)
+            .max()
+            .unwrap_or_default();
+        let current = &seats[seat];
+        let to_call = max_bet.saturating_sub(current.bet);
+        let opponent_can_act = seats
+            .iter()
+            .enumerate()
+            .any(|(i, s)| i != seat && s.can_act());
+
+        let raise_range = match limits.bounds(to_call) {
+            Some((min, max)) if current.stack > to_call && opponent_can_act => {
+                let max = max.min(current.stack - to_call);
+                Some((min.min(max), max))
+            }
+            _ => None,
+        };
 
-impl From<&Game> for ObservableState {
-    fn from(value: &Game) -> Self {
-        Self {}
+        LegalActions {
+            call_amount: to_call.min(current.stack),
+            raise_range,
+        }
+    }
+
+    pub fn can_check(&self) -> bool {
+        self.call_amount.is_zero()
+    }
+
+    pub fn contains(&self, action: Action) -> bool {
+        match action {
+            Action::Fold => true,
+            Action::Check => self.can_check(),
+            Action::Call => !self.can_check(),
+            Action::Raise(amount) => self
+                .raise_range
+                .is_some_and(|(min, max)| min <= amount && amount <= max),
+        }
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionRecord {
+    pub seat: usize,
+    pub round: Round,
+    pub action: Action,
+}
+
+/// `Raise` holds the amount added on top of what is needed to call
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Action {
     Fold,
-    Raise(usize),
+    Raise(Chips),
     Call,
     Check,
 }
 
 #[test]
 fn functional_test() -> Result<(), GameError> {
-    let n = 3;
+    use crate::*;
+
+    let n = 5;
     let settings = Settings {
         n_players: n,
-        initial_stack: 1000,
+        initial_stack: Chips::new(1000),
+        small_blind: Chips::new(10),
+        big_blind: Chips::new(20),
+        ..Default::default()
     };
-    let players = vec![Player::new(); n];
-    let mut game = Game::new(settings);
+    let mut agents: Vec<Box<dyn Agent>> = vec![
+        Box::new(RandomAgent::new(0)),
+        Box::new(CallingAgent),
+        Box::new(RaisingAgent),
+        Box::new(TightAggressiveAgent::new(1)),
+        Box::new(PotOddsAgent::new(2)),
+    ];
+    for n_players in [0, 1] {
+        let lonely = Settings {
+            n_players,
+            ..settings.clone()
+        };
+        assert!(matches!(Game::new(lonely), Err(GameError::InvalidSettings)));
+    }
+    let mut game = Game::with_seed(settings, 0)?;
+    assert!(matches!(
+        play_hand(&mut game, &mut agents[..n - 1]),
+        Err(GameError::AgentCountMismatch { expected, got }) if expected == n && got == n - 1
+    ));
 
     while !game.over() {
-        let seat_number = game.current_seat();
-
-        let mut player = players[seat_number];
-        let action = player.choose_action();
-        game.play_turn(action)?;
+        play_hand(&mut game, &mut agents)?;
     }
 
     Ok(())
 }
+
+#[test]
+fn side_pots() {
+    let settings = Settings {
+        n_players: 3,
+        initial_stack: Chips::new(1000),
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        ..Default::default()
+    };
+    let card = |suit, value| Card::new(suit, value);
+    let pair = |value| PlayerHand::new(card(Suit::Spades, value), card(Suit::Hearts, value));
+    let setup = HandSetup {
+        stacks: vec![Chips::new(100), Chips::new(1000), Chips::new(1000)],
+        sb_seat: 0,
+        hands: vec![
+            Some(pair(Value::Ace)),
+            Some(pair(Value::King)),
+            Some(pair(Value::Queen)),
+        ],
+        board: vec![
+            card(Suit::Clubs, Value::Two),
+            card(Suit::Diamonds, Value::Seven),
+            card(Suit::Hearts, Value::Nine),
+            card(Suit::Clubs, Value::Jack),
+            card(Suit::Spades, Value::Three),
+        ],
+    };
+    let mut game = Game::from_setup(settings, &setup, 0).unwrap();
+
+    // the short small blind shoves, the others call and build a side pot on the flop
+    game.play_turn(Action::Call).unwrap();
+    game.play_turn(Action::Raise(Chips::new(90))).unwrap();
+    game.play_turn(Action::Call).unwrap();
+    game.play_turn(Action::Call).unwrap();
+    game.play_turn(Action::Raise(Chips::new(100))).unwrap();
+    game.play_turn(Action::Call).unwrap();
+    while game.hand_count() == 0 {
+        game.play_turn(Action::Check).unwrap();
+    }
+
+    // aces take the main pot of 300, kings the side pot of 200 the aces cannot win
+    let result = game.last_hand_result().unwrap();
+    assert!(result.showdown);
+    assert_eq!(result.deltas, vec![200, 0, -200]);
+    assert_eq!(
+        game.last_hand_history().unwrap().awards,
+        vec![Chips::new(300), Chips::new(200), Chips::ZERO]
+    );
+}
+
+#[test]
+fn table_limits() {
+    let settings = Settings {
+        n_players: MAX_PLAYERS,
+        initial_stack: Chips::new(1000),
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        ..Default::default()
+    };
+    assert!(settings.validate().is_ok());
+    let game = Game::with_seed(settings.clone(), 0).unwrap();
+    assert!(game.hands().iter().all(Option::is_some));
+
+    // the deck cannot deal two cards to every seat and a board
+    let crowded = Settings {
+        n_players: MAX_PLAYERS + 1,
+        ..settings.clone()
+    };
+    assert!(matches!(
+        crowded.validate(),
+        Err(GameError::InvalidSettings)
+    ));
+
+    // the chips on the table must add up without overflowing
+    let deep = Settings {
+        n_players: 3,
+        initial_stack: Chips::new(usize::MAX / 2),
+        ..settings
+    };
+    assert!(matches!(deep.validate(), Err(GameError::InvalidSettings)));
+    let setup = HandSetup {
+        stacks: vec![
+            Chips::new(usize::MAX / 3),
+            Chips::new(usize::MAX / 3),
+            Chips::MAX,
+        ],
+        ..Default::default()
+    };
+    let settings = Settings {
+        initial_stack: Chips::new(1000),
+        ..deep
+    };
+    assert!(matches!(
+        Game::from_setup(settings, &setup, 0),
+        Err(GameError::InvalidSetup)
+    ));
+}
+
+#[test]
+fn betting_structures() {
+    let pot_limit = Settings {
+        n_players: 3,
+        initial_stack: Chips::new(1000),
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        betting: BettingStructure::PotLimit,
+        ..Default::default()
+    };
+    let mut game = Game::with_seed(pot_limit.clone(), 0).unwrap();
+    // calling 10 makes a pot of 25
+    assert_eq!(
+        game.legal_actions().raise_range,
+        Some((Chips::new(10), Chips::new(25)))
+    );
+    assert!(matches!(
+        game.play_turn(Action::Raise(Chips::new(26))),
+        Err(GameError::InvalidAction)
+    ));
+    game.play_turn(Action::Raise(Chips::new(25))).unwrap();
+    // the small blind calls 30 into a pot of 50
+    assert_eq!(
+        game.legal_actions().raise_range,
+        Some((Chips::new(25), Chips::new(80)))
+    );
+
+    let fixed_limit = Settings {
+        betting: BettingStructure::FixedLimit {
+            small_bet: Chips::new(10),
+            big_bet: Chips::new(20),
+            max_bets: 4,
+        },
+        ..pot_limit
+    };
+    let mut game = Game::with_seed(fixed_limit.clone(), 0).unwrap();
+    assert_eq!(
+        game.legal_actions().raise_range,
+        Some((Chips::new(10), Chips::new(10)))
+    );
+    for _ in 0..3 {
+        game.play_turn(Action::Raise(Chips::new(10))).unwrap();
+    }
+    // the big blind and three raises cap the street
+    assert_eq!(game.legal_actions().raise_range, None);
+    assert!(matches!(
+        game.play_turn(Action::Raise(Chips::new(10))),
+        Err(GameError::InvalidAction)
+    ));
+    game.play_turn(Action::Call).unwrap();
+    game.play_turn(Action::Call).unwrap();
+    assert_eq!(game.get_observable_state(0).round, Round::Flop);
+    assert_eq!(
+        game.legal_actions().raise_range,
+        Some((Chips::new(10), Chips::new(10)))
+    );
+    for _ in 0..3 {
+        game.play_turn(Action::Check).unwrap();
+    }
+    assert_eq!(
+        game.legal_actions().raise_range,
+        Some((Chips::new(20), Chips::new(20)))
+    );
+
+    let invalid = Settings {
+        betting: BettingStructure::FixedLimit {
+            small_bet: Chips::new(10),
+            big_bet: Chips::new(20),
+            max_bets: 0,
+        },
+        ..fixed_limit
+    };
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn rake() {
+    let rake = Rake {
+        basis_points: 500,
+        caps: vec![(2, Chips::new(10)), (4, Chips::new(30))],
+        no_flop_no_drop: true,
+    };
+    assert_eq!(rake.amount(Chips::new(100), 2, false), Chips::new(0));
+    assert_eq!(rake.amount(Chips::new(100), 2, true), Chips::new(5));
+    assert_eq!(rake.amount(Chips::new(1000), 3, true), Chips::new(10));
+    assert_eq!(rake.amount(Chips::new(1000), 6, true), Chips::new(30));
+    assert_eq!(rake.amount(Chips::new(1000), 1, true), Chips::new(50));
+    // huge pots are raked without overflowing
+    let uncapped = Rake {
+        basis_points: 10_000,
+        ..Default::default()
+    };
+    assert_eq!(uncapped.amount(Chips::MAX, 2, true), Chips::MAX);
+
+    let settings = Settings {
+        n_players: 3,
+        initial_stack: Chips::new(1000),
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        rake,
+        ..Default::default()
+    };
+    assert!(
+        Settings {
+            rake: Rake {
+                basis_points: 10_001,
+                ..Default::default()
+            },
+            ..settings.clone()
+        }
+        .validate()
+        .is_err()
+    );
+    assert!(
+        Settings {
+            denomination: Denomination {
+                symbol: "$".to_string(),
+                decimals: 20,
+            },
+            ..settings.clone()
+        }
+        .validate()
+        .is_err()
+    );
+    let mut game = Game::with_seed(settings, 0).unwrap();
+
+    // no flop, no drop
+    game.play_turn(Action::Fold).unwrap();
+    game.play_turn(Action::Fold).unwrap();
+    assert_eq!(game.last_hand_result().unwrap().rake, Chips::ZERO);
+
+    // the uncalled bet goes back unraked
+    game.play_turn(Action::Call).unwrap();
+    game.play_turn(Action::Call).unwrap();
+    game.play_turn(Action::Check).unwrap();
+    game.play_turn(Action::Raise(Chips::new(100))).unwrap();
+    game.play_turn(Action::Fold).unwrap();
+    game.play_turn(Action::Fold).unwrap();
+    let result = game.last_hand_result().unwrap();
+    assert_eq!(result.rake, Chips::new(1));
+    assert_eq!(result.deltas.iter().sum::<isize>(), -1);
+
+    // capped by the seats dealt in, taken before splitting the pots
+    game.play_turn(Action::Raise(Chips::new(990))).unwrap();
+    game.play_turn(Action::Call).unwrap();
+    game.play_turn(Action::Call).unwrap();
+    let result = game.last_hand_result().unwrap();
+    assert_eq!(result.rake, Chips::new(10));
+    assert_eq!(result.deltas.iter().sum::<isize>(), -10);
+    assert_eq!(game.last_hand_history().unwrap().rake, Chips::new(10));
+}
+
+#[test]
+fn snapshot_and_restore() {
+    use crate::*;
+
+    let settings = Settings {
+        n_players: 3,
+        initial_stack: Chips::new(1000),
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        ..Default::default()
+    };
+    let mut game = Game::with_seed(settings, 3).unwrap();
+    game.play_turn(Action::Call).unwrap();
+    let snapshot = game.snapshot();
+    let branch = game.clone();
+
+    // roll out a few hands, then rewind
+    let mut agents: Vec<Box<dyn Agent>> = vec![
+        Box::new(RandomAgent::new(0)),
+        Box::new(CallingAgent),
+        Box::new(RaisingAgent),
+    ];
+    for _ in 0..3 {
+        if !game.over() {
+            play_hand(&mut game, &mut agents).unwrap();
+        }
+    }
+    assert_ne!(game.snapshot(), branch.snapshot());
+    game.restore(&snapshot);
+    assert_eq!(game.snapshot(), branch.snapshot());
+
+    // both copies deal the same cards from here
+    let mut branch = branch;
+    while game.hand_count() == 0 {
+        game.play_turn(Action::Call)
+            .or_else(|_| game.play_turn(Action::Check))
+            .unwrap();
+        branch
+            .play_turn(Action::Call)
+            .or_else(|_| branch.play_turn(Action::Check))
+            .unwrap();
+    }
+    assert_eq!(game.snapshot(), branch.snapshot());
+
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_string(&game).unwrap();
+        let mut copy: Game = serde_json::from_str(&json).unwrap();
+        assert_eq!(copy, game);
+        copy.play_turn(Action::Call).unwrap();
+        game.play_turn(Action::Call).unwrap();
+        assert_eq!(copy, game);
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored: GameSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, snapshot);
+
+        let bytes = game.to_bytes();
+        let mut copy = Game::from_bytes(&bytes).unwrap();
+        assert_eq!(copy, game);
+        copy.play_turn(Action::Call).unwrap();
+        game.play_turn(Action::Call).unwrap();
+        assert_eq!(copy, game);
+        assert!(matches!(
+            Game::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(GameError::InvalidBytes)
+        ));
+        assert!(matches!(
+            Game::from_bytes(&[0; 4]),
+            Err(GameError::InvalidBytes)
+        ));
+    }
+}
+
+#[test]
+fn undo_and_redo() {
+    use crate::*;
+
+    let settings = Settings {
+        n_players: 3,
+        initial_stack: Chips::new(200),
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        ..Default::default()
+    };
+    let mut game = Game::with_seed(settings, 5).unwrap();
+    assert!(!game.can_undo());
+    let start = game.clone();
+
+    let mut agents: Vec<Box<dyn Agent>> = vec![
+        Box::new(RandomAgent::new(0)),
+        Box::new(CallingAgent),
+        Box::new(RandomAgent::new(1)),
+    ];
+    let mut states = vec![game.clone()];
+    let mut actions = Vec::new();
+    while game.hand_count() < 2 && !game.over() {
+        let state = game.get_observable_state(game.current_seat());
+        let action = agents[game.current_seat()].choose_action(&state, &game.legal_actions());
+        game.play_turn(action).unwrap();
+        actions.push(action);
+        states.push(game.clone());
+    }
+    let end = game.clone();
+
+    // the first hand is out of reach once the second is over
+    let first_hand = states
+        .iter()
+        .filter(|state| state.hand_count() == 0)
+        .count();
+    let without_log = |game: &Game| GameState {
+        transitions: Vec::new(),
+        ..game.game_state.clone()
+    };
+    for (i, &action) in actions.iter().enumerate().skip(first_hand).rev() {
+        assert_eq!(game.undo(), Some(action));
+        assert_eq!(without_log(&game), without_log(&states[i]));
+        assert_eq!(game.hand_count(), states[i].hand_count());
+        assert_eq!(game.last_hand_history(), states[i].last_hand_history());
+    }
+    assert_eq!(game.undo(), None);
+
+    while game.redo().is_some() {}
+    assert_eq!(game.snapshot(), end.snapshot());
+
+    // a new action clears what could be redone
+    let mut game = start;
+    let legal = game.legal_actions();
+    game.play_turn(Action::Call).unwrap();
+    game.undo();
+    assert!(game.can_redo());
+    game.play_turn(Action::Raise(legal.raise_range.unwrap().0))
+        .unwrap();
+    assert!(!game.can_redo());
+}
@@ -0,0 +1,640 @@
+use crate::*;
+use std::fmt::Write;
+
+/// Action of a hand history, with the chips it moved
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HistoryAction {
+    pub seat: usize,
+    pub round: Round,
+    pub action: Action,
+    /// Chips put in the pot by the action
+    pub amount: Chips,
+    /// Bet of the seat in the round once the action is played
+    pub bet: Chips,
+    pub all_in: bool,
+}
+
+/// Complete record of a finished hand, from the blinds to the pot awards
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandHistory {
+    /// Hands are numbered from 1 in every game
+    pub hand_number: usize,
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    pub betting: BettingStructure,
+    pub button: usize,
+    /// Blinds actually posted, as seat and amount, small blind first
+    pub blinds: Vec<(usize, Chips)>,
+    /// Stacks at the start of the hand, before the blinds, zero for the seats out of the game
+    pub stacks: Vec<Chips>,
+    pub hands: Vec<Option<PlayerHand>>,
+    pub actions: Vec<HistoryAction>,
+    pub board: Vec<Card>,
+    pub showdown: bool,
+    /// Hands revealed at showdown, by seat
+    pub shown_hands: Vec<(usize, PlayerHand)>,
+    /// Chips every seat collected at the end of the hand, uncalled bets included
+    pub awards: Vec<Chips>,
+    /// Chips taken from the pot by the house
+    pub rake: Chips,
+    /// How amounts are written in the PokerStars export
+    pub denomination: Denomination,
+}
+
+impl HandHistory {
+    /// Rebuilds the chips moved by every action of a hand from the stacks it started with
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        hand_number: usize,
+        settings: &Settings,
+        sb_seat: usize,
+        bb_seat: usize,
+        stacks: &[Chips],
+        hands: &[Option<PlayerHand>],
+        records: &[ActionRecord],
+        board: Vec<Card>,
+        result: &HandResult,
+        awards: Vec<Chips>,
+    ) -> Self {
+        let mut remaining = stacks.to_vec();
+        let mut bets = vec![Chips::ZERO; stacks.len()];
+        let mut blinds = Vec::new();
+        for (seat, blind) in [
+            (sb_seat, settings.small_blind),
+            (bb_seat, settings.big_blind),
+        ] {
+            let amount = blind.min(remaining[seat]);
+            remaining[seat] -= amount;
+            bets[seat] += amount;
+            blinds.push((seat, amount));
+        }
+
+        let mut round = Round::PreFlop;
+        let mut actions = Vec::with_capacity(records.len());
+        for record in records {
+            if record.round != round {
+                round = record.round;
+                bets.iter_mut().for_each(|bet| *bet = Chips::ZERO);
+            }
+            let seat = record.seat;
+            let to_call = bets.iter().max().copied().unwrap_or_default() - bets[seat];
+            let amount = match record.action {
+                Action::Fold | Action::Check => Chips::ZERO,
+                Action::Call => to_call,
+                Action::Raise(raise) => to_call + raise,
+            }
+            .min(remaining[seat]);
+            remaining[seat] -= amount;
+            bets[seat] += amount;
+            actions.push(HistoryAction {
+                seat,
+                round,
+                action: record.action,
+                amount,
+                bet: bets[seat],
+                all_in: !amount.is_zero() && remaining[seat].is_zero(),
+            });
+        }
+
+        let live = |seat: usize| !stacks[seat].is_zero();
+        let n = stacks.len();
+        // heads-up the small blind has the button
+        let button = if (0..n).filter(|&seat| live(seat)).count() == 2 {
+            sb_seat
+        } else {
+            (1..n)
+                .map(|i| (sb_seat + n - i) % n)
+                .find(|&seat| live(seat))
+                .unwrap_or(sb_seat)
+        };
+
+        HandHistory {
+            hand_number,
+            small_blind: settings.small_blind,
+            big_blind: settings.big_blind,
+            betting: settings.betting,
+            button,
+            blinds,
+            stacks: stacks.to_vec(),
+            hands: hands.to_vec(),
+            actions,
+            board,
+            showdown: result.showdown,
+            shown_hands: result.shown_hands.clone(),
+            awards,
+            rake: result.rake,
+            denomination: settings.denomination.clone(),
+        }
+    }
+
+    /// Chips every seat put in the pot
+    pub fn committed(&self) -> Vec<Chips> {
+        let mut committed = vec![Chips::ZERO; self.stacks.len()];
+        for &(seat, amount) in &self.blinds {
+            committed[seat] += amount;
+        }
+        for action in &self.actions {
+            committed[action.seat] += action.amount;
+        }
+        committed
+    }
+
+    /// Bet nobody called, with the seat it goes back to
+    pub fn uncalled_bet(&self) -> Option<(usize, Chips)> {
+        let committed = self.committed();
+        let (top, &highest) = committed
+            .iter()
+            .enumerate()
+            .max_by_key(|(seat, amount)| (**amount, std::cmp::Reverse(*seat)))?;
+        let second = committed
+            .iter()
+            .enumerate()
+            .filter(|&(seat, _)| seat != top)
+            .map(|(_, &amount)| amount)
+            .max()
+            .unwrap_or_default();
+        (highest > second).then_some((top, highest - second))
+    }
+
+    /// Name of a seat in the exported histories
+    pub fn player_name(seat: usize) -> String {
+        format!("player{}", seat + 1)
+    }
+
+    /// History as a JSON object, `timestamp` being the seconds since the Unix epoch the hand
+    /// ended at
+    ///
+    /// The object is written by hand rather than with serde, so the export works without the
+    /// `serde` feature and keeps a flat layout for other tools, with cards as text and actions
+    /// by name, whatever the serialized shape of the game types. Amounts are counted in chips,
+    /// the `denomination` telling what a chip is worth.
+    pub fn to_json(&self, timestamp: u64) -> String {
+        let cards = |cards: &mut dyn Iterator<Item = &Card>| {
+            let cards: Vec<String> = cards.map(|card| format!("\"{card}\"")).collect();
+            format!("[{}]", cards.join(","))
+        };
+        let list = |items: Vec<String>| format!("[{}]", items.join(","));
+
+        let hands = self
+            .hands
+            .iter()
+            .map(|hand| match hand {
+                Some(hand) => cards(&mut hand.cards()),
+                None => "null".to_string(),
+            })
+            .collect();
+        let actions = self
+            .actions
+            .iter()
+            .map(|action| {
+                let (name, raise) = match action.action {
+                    Action::Fold => ("fold", Chips::ZERO),
+                    Action::Check => ("check", Chips::ZERO),
+                    Action::Call => ("call", Chips::ZERO),
+                    Action::Raise(raise) => ("raise", raise),
+                };
+                format!(
+                    "{{\"seat\":{},\"round\":\"{}\",\"action\":\"{name}\",\"raise\":{raise},\"amount\":{},\"bet\":{},\"all_in\":{}}}",
+                    action.seat,
+                    round_name(action.round),
+                    action.amount,
+                    action.bet,
+                    action.all_in
+                )
+            })
+            .collect();
+        let shown_hands = self
+            .shown_hands
+            .iter()
+            .map(|(seat, hand)| {
+                format!("{{\"seat\":{seat},\"cards\":{}}}", cards(&mut hand.cards()))
+            })
+            .collect();
+        let numbers = |numbers: &[Chips]| list(numbers.iter().map(Chips::to_string).collect());
+        let betting = match self.betting {
+            BettingStructure::NoLimit => "{\"structure\":\"no_limit\"}".to_string(),
+            BettingStructure::PotLimit => "{\"structure\":\"pot_limit\"}".to_string(),
+            BettingStructure::FixedLimit {
+                small_bet,
+                big_bet,
+                max_bets,
+            } => format!(
+                "{{\"structure\":\"fixed_limit\",\"small_bet\":{small_bet},\"big_bet\":{big_bet},\"max_bets\":{max_bets}}}"
+            ),
+        };
+        let symbol: String = self
+            .denomination
+            .symbol
+            .chars()
+            .map(|c| match c {
+                '"' | '\\' => format!("\\{c}"),
+                c if c.is_control() => format!("\\u{:04x}", c as u32),
+                c => c.to_string(),
+            })
+            .collect();
+
+        format!(
+            "{{\"hand_number\":{},\"timestamp\":{},\"small_blind\":{},\"big_blind\":{},\"betting\":{},\"denomination\":{{\"symbol\":\"{}\",\"decimals\":{}}},\"button\":{},\"blinds\":{},\"stacks\":{},\"hands\":{},\"actions\":{},\"board\":{},\"showdown\":{},\"shown_hands\":{},\"awards\":{},\"rake\":{}}}",
+            self.hand_number,
+            timestamp,
+            self.small_blind,
+            self.big_blind,
+            betting,
+            symbol,
+            self.denomination.decimals,
+            self.button,
+            list(
+                self.blinds
+                    .iter()
+                    .map(|(seat, amount)| format!("[{seat},{amount}]"))
+                    .collect()
+            ),
+            numbers(&self.stacks),
+            list(hands),
+            list(actions),
+            cards(&mut self.board.iter()),
+            self.showdown,
+            list(shown_hands),
+            numbers(&self.awards),
+            self.rake,
+        )
+    }
+
+    /// History in the text format of PokerStars, read by most trackers and review tools
+    ///
+    /// The hole cards of every seat are dealt in the open, and amounts are written in the
+    /// denomination of the game. The hand is dated by `timestamp`, in seconds since the Unix
+    /// epoch, which the game leaves to the caller so that replaying a hand gives the same
+    /// history.
+    pub fn to_pokerstars(&self, timestamp: u64) -> String {
+        let name = Self::player_name;
+        let money = |chips: Chips| self.denomination.format(chips);
+        let mut text = String::new();
+        // writing to a String never fails
+        let mut line = |line: String| writeln!(text, "{line}").unwrap();
+        let n = self.stacks.len();
+
+        // fixed-limit games go by their bet sizes, the blinds showing in the posts
+        let (game, stakes) = match self.betting {
+            BettingStructure::NoLimit => ("No Limit", (self.small_blind, self.big_blind)),
+            BettingStructure::PotLimit => ("Pot Limit", (self.small_blind, self.big_blind)),
+            BettingStructure::FixedLimit {
+                small_bet, big_bet, ..
+            } => ("Limit", (small_bet, big_bet)),
+        };
+        line(format!(
+            "PokerStars Hand #{}: Hold'em {game} ({}/{}) - {} UTC",
+            self.hand_number,
+            self.denomination.format(stakes.0),
+            self.denomination.format(stakes.1),
+            format_timestamp(timestamp)
+        ));
+        line(format!(
+            "Table 'pokr' {n}-max Seat #{} is the button",
+            self.button + 1
+        ));
+        for (seat, &stack) in self.stacks.iter().enumerate() {
+            if !stack.is_zero() {
+                line(format!(
+                    "Seat {}: {} ({} in chips)",
+                    seat + 1,
+                    name(seat),
+                    money(stack)
+                ));
+            }
+        }
+        for (&(seat, amount), blind) in self.blinds.iter().zip(["small", "big"]) {
+            line(format!(
+                "{}: posts {blind} blind {}",
+                name(seat),
+                money(amount)
+            ));
+        }
+
+        line("*** HOLE CARDS ***".to_string());
+        for (seat, hand) in self.hands.iter().enumerate() {
+            if let Some(hand) = hand {
+                line(format!(
+                    "Dealt to {} [{}]",
+                    name(seat),
+                    join_cards(hand.cards())
+                ));
+            }
+        }
+
+        let mut round = Round::PreFlop;
+        let mut max_bet = self
+            .blinds
+            .iter()
+            .map(|(_, amount)| *amount)
+            .max()
+            .unwrap_or_default();
+        let street = |round: Round, line: &mut dyn FnMut(String)| {
+            let cards = round.n_cards();
+            if cards > self.board.len() {
+                return;
+            }
+            let (title, previous) = match round {
+                Round::PreFlop => return,
+                Round::Flop => ("FLOP", 0),
+                Round::Turn => ("TURN", 3),
+                Round::River => ("RIVER", 4),
+            };
+            let new = join_cards(self.board[previous..cards].iter());
+            if previous == 0 {
+                line(format!("*** {title} *** [{new}]"));
+            } else {
+                let old = join_cards(self.board[..previous].iter());
+                line(format!("*** {title} *** [{old}] [{new}]"));
+            }
+        };
+
+        for action in &self.actions {
+            while round != action.round {
+                // the rounds of the actions always follow each other
+                round = round.next().unwrap();
+                max_bet = Chips::ZERO;
+                street(round, &mut line);
+            }
+            let player = name(action.seat);
+            let all_in = if action.all_in { " and is all-in" } else { "" };
+            match action.action {
+                Action::Fold => line(format!("{player}: folds")),
+                Action::Check => line(format!("{player}: checks")),
+                Action::Call => line(format!("{player}: calls {}{all_in}", money(action.amount))),
+                Action::Raise(_) if max_bet.is_zero() => {
+                    line(format!("{player}: bets {}{all_in}", money(action.amount)))
+                }
+                Action::Raise(_) => line(format!(
+                    "{player}: raises {} to {}{all_in}",
+                    money(action.bet.saturating_sub(max_bet)),
+                    money(action.bet)
+                )),
+            }
+            max_bet = max_bet.max(action.bet);
+        }
+        while let Some(next) = round
+            .next()
+            .filter(|next| next.n_cards() <= self.board.len())
+        {
+            round = next;
+            street(round, &mut line);
+        }
+
+        let uncalled = self.uncalled_bet();
+        if let Some((seat, amount)) = uncalled {
+            line(format!(
+                "Uncalled bet ({}) returned to {}",
+                money(amount),
+                name(seat)
+            ));
+        }
+        let won: Vec<Chips> = (0..n)
+            .map(|seat| match uncalled {
+                Some((uncalled_seat, amount)) if uncalled_seat == seat => {
+                    self.awards[seat].saturating_sub(amount)
+                }
+                _ => self.awards[seat],
+            })
+            .collect();
+        if self.showdown {
+            line("*** SHOW DOWN ***".to_string());
+            for (seat, hand) in &self.shown_hands {
+                let rank = HandRank::evaluate(hand.cards().chain(&self.board));
+                line(format!(
+                    "{}: shows [{}] ({})",
+                    name(*seat),
+                    join_cards(hand.cards()),
+                    hand_type_name(rank.hand_type())
+                ));
+            }
+        }
+        for (seat, &amount) in won.iter().enumerate() {
+            if !amount.is_zero() {
+                line(format!(
+                    "{} collected {} from pot",
+                    name(seat),
+                    money(amount)
+                ));
+            }
+        }
+
+        line("*** SUMMARY ***".to_string());
+        line(format!(
+            "Total pot {} | Rake {}",
+            money(won.iter().sum::<Chips>() + self.rake),
+            money(self.rake)
+        ));
+        if !self.board.is_empty() {
+            line(format!("Board [{}]", join_cards(self.board.iter())));
+        }
+        for seat in (0..n).filter(|&seat| !self.stacks[seat].is_zero()) {
+            let mut summary = format!("Seat {}: {}", seat + 1, name(seat));
+            if seat == self.button {
+                summary += " (button)";
+            }
+            if self.blinds.first().is_some_and(|(s, _)| *s == seat) {
+                summary += " (small blind)";
+            } else if self.blinds.get(1).is_some_and(|(s, _)| *s == seat) {
+                summary += " (big blind)";
+            }
+            let folded = self
+                .actions
+                .iter()
+                .find(|action| action.seat == seat && action.action == Action::Fold);
+            let shown = self.shown_hands.iter().find(|(s, _)| *s == seat);
+            summary += &match (folded, shown) {
+                (Some(action), _) if action.round == Round::PreFlop => {
+                    " folded before Flop".to_string()
+                }
+                (Some(action), _) => format!(" folded on the {}", street_name(action.round)),
+                (None, Some((_, hand))) if !won[seat].is_zero() => {
+                    format!(
+                        " showed [{}] and won ({})",
+                        join_cards(hand.cards()),
+                        money(won[seat])
+                    )
+                }
+                (None, Some((_, hand))) => {
+                    format!(" showed [{}] and lost", join_cards(hand.cards()))
+                }
+                (None, None) => format!(" collected ({})", money(won[seat])),
+            };
+            line(summary);
+        }
+        text
+    }
+}
+
+fn join_cards<'a>(cards: impl Iterator<Item = &'a Card>) -> String {
+    cards.map(Card::to_string).collect::<Vec<_>>().join(" ")
+}
+
+fn round_name(round: Round) -> &'static str {
+    match round {
+        Round::PreFlop => "preflop",
+        Round::Flop => "flop",
+        Round::Turn => "turn",
+        Round::River => "river",
+    }
+}
+
+fn street_name(round: Round) -> &'static str {
+    match round {
+        Round::PreFlop => "Preflop",
+        Round::Flop => "Flop",
+        Round::Turn => "Turn",
+        Round::River => "River",
+    }
+}
+
+fn hand_type_name(hand_type: HandType) -> &'static str {
+    match hand_type {
+        HandType::StraightFlush => "a straight flush",
+        HandType::FourOfAKind => "four of a kind",
+        HandType::FullHouse => "a full house",
+        HandType::Flush => "a flush",
+        HandType::Straight => "a straight",
+        HandType::ThreeOfAKind => "three of a kind",
+        HandType::DoublePair => "two pair",
+        HandType::Pair => "a pair",
+        HandType::HighCard => "high card",
+    }
+}
+
+/// `YYYY/MM/DD HH:MM:SS` date of a Unix timestamp
+fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let seconds = timestamp % 86400;
+    // civil date from days since the epoch, after Howard Hinnant
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{year:04}/{month:02}/{day:02} {:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[test]
+fn hand_history_export() {
+    let settings = Settings {
+        n_players: 3,
+        initial_stack: Chips::new(1000),
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        ..Default::default()
+    };
+    let mut game = Game::with_seed(settings.clone(), 1).unwrap();
+    // the button raises, the small blind folds, the big blind calls then folds to a flop bet
+    game.play_turn(Action::Raise(Chips::new(20))).unwrap();
+    game.play_turn(Action::Fold).unwrap();
+    game.play_turn(Action::Call).unwrap();
+    game.play_turn(Action::Check).unwrap();
+    game.play_turn(Action::Raise(Chips::new(40))).unwrap();
+    game.play_turn(Action::Fold).unwrap();
+
+    let history = game.last_hand_history().unwrap();
+    assert_eq!(history.button, 2);
+    assert_eq!(history.uncalled_bet(), Some((2, Chips::new(40))));
+    let text = history.to_pokerstars(1_700_000_000);
+    assert!(
+        text.starts_with("PokerStars Hand #1: Hold'em No Limit (5/10) - 2023/11/14 22:13:20 UTC")
+    );
+    for line in [
+        "Table 'pokr' 3-max Seat #3 is the button",
+        "player2: posts big blind 10",
+        "player3: raises 20 to 30",
+        "player2: calls 20",
+        "player3: bets 40",
+        "Uncalled bet (40) returned to player3",
+        "player3 collected 65 from pot",
+        "Total pot 65 | Rake 0",
+        "Seat 2: player2 (big blind) folded on the Flop",
+    ] {
+        assert!(text.lines().any(|l| l == line), "missing {line}");
+    }
+    let json = history.to_json(1_700_000_000);
+    assert!(json.contains("\"timestamp\":1700000000"));
+    assert!(json.contains("\"blinds\":[[0,5],[1,10]]"));
+    assert!(json.contains("\"awards\":[0,0,105]"));
+    assert!(json.contains(
+        "\"betting\":{\"structure\":\"no_limit\"},\"denomination\":{\"symbol\":\"\",\"decimals\":0}"
+    ));
+
+    // a chip is a cent, and the amounts read back the same
+    let cents = Settings {
+        denomination: Denomination {
+            symbol: "$".to_string(),
+            decimals: 2,
+        },
+        ..settings.clone()
+    };
+    let mut game = Game::with_seed(cents, 1).unwrap();
+    game.play_turn(Action::Raise(Chips::new(20))).unwrap();
+    game.play_turn(Action::Fold).unwrap();
+    game.play_turn(Action::Fold).unwrap();
+    let history = game.last_hand_history().unwrap();
+    let text = history.to_pokerstars(0);
+    assert!(text.contains("Hold'em No Limit ($0.05/$0.10)"));
+    assert!(text.contains("Seat 1: player1 ($10.00 in chips)"));
+    assert!(text.contains("player3: raises $0.20 to $0.30"));
+    assert!(
+        history
+            .to_json(0)
+            .contains("\"denomination\":{\"symbol\":\"$\",\"decimals\":2}")
+    );
+    let parsed = parse_pokerstars(&text).unwrap();
+    assert_eq!(replay(&parsed[0], 0).unwrap().awards, history.awards);
+
+    // the betting structure is written down and read back
+    for (betting, header) in [
+        (BettingStructure::PotLimit, "Hold'em Pot Limit (5/10)"),
+        (
+            BettingStructure::FixedLimit {
+                small_bet: Chips::new(10),
+                big_bet: Chips::new(20),
+                max_bets: 4,
+            },
+            "Hold'em Limit (10/20)",
+        ),
+    ] {
+        let mut game = Game::with_seed(
+            Settings {
+                betting,
+                ..settings.clone()
+            },
+            1,
+        )
+        .unwrap();
+        game.play_turn(Action::Raise(Chips::new(10))).unwrap();
+        game.play_turn(Action::Fold).unwrap();
+        game.play_turn(Action::Fold).unwrap();
+        let history = game.last_hand_history().unwrap();
+        let text = history.to_pokerstars(0);
+        assert!(text.contains(header), "missing {header}");
+        let parsed = parse_pokerstars(&text).unwrap();
+        assert_eq!(parsed[0].settings.betting, betting);
+        assert_eq!(replay(&parsed[0], 0).unwrap().awards, history.awards);
+        let structure = if header.contains("Pot Limit") {
+            "{\"structure\":\"pot_limit\"}"
+        } else {
+            "{\"structure\":\"fixed_limit\",\"small_bet\":10,\"big_bet\":20,\"max_bets\":4}"
+        };
+        assert!(
+            history.to_json(0).contains(structure),
+            "missing {structure}"
+        );
+    }
+}
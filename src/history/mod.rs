@@ -0,0 +1,60 @@
+mod hand_history;
+mod phh;
+mod pokerstars;
+mod replay;
+
+pub use hand_history::*;
+pub use phh::*;
+pub use pokerstars::*;
+pub use replay::*;
+
+use crate::*;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum HistoryError {
+    #[error("Line {line}: {message}")]
+    Parse { line: usize, message: String },
+    #[error("Unsupported hand: {0}")]
+    Unsupported(String),
+    #[error("Replay does not match the history: {0}")]
+    Mismatch(String),
+    #[error(transparent)]
+    Game(#[from] GameError),
+}
+
+/// Action read from a hand history
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ParsedAction {
+    pub seat: usize,
+    pub action: Action,
+    /// Chips the history says the action put in the pot
+    pub amount: Chips,
+}
+
+/// Hand read from a hand history, ready to be replayed through a `Game`
+#[derive(Clone, Debug, Default)]
+pub struct ParsedHand {
+    /// Identifier of the hand in its file
+    pub id: String,
+    pub settings: Settings,
+    pub setup: HandSetup,
+    pub actions: Vec<ParsedAction>,
+    /// Pot the history reports, uncalled bets excluded
+    pub pot: Option<Chips>,
+    pub rake: Chips,
+    /// Stacks the history reports at the end of the hand
+    pub final_stacks: Option<Vec<Chips>>,
+}
+
+fn parse_cards(text: &str) -> Option<Vec<Card>> {
+    Card::parse_list(text).ok()
+}
+
+/// Two hole cards, `None` when the text holds anything else
+fn parse_hand(text: &str) -> Option<PlayerHand> {
+    match parse_cards(text)?.as_slice() {
+        &[first, second] => Some(PlayerHand::new(first, second)),
+        _ => None,
+    }
+}
@@ -0,0 +1,357 @@
+use crate::history::*;
+
+/// Value of the TOML subset used by PHH files
+#[derive(Clone, Debug, PartialEq)]
+enum PhhValue {
+    /// Text of the number, which amounts are read from exactly
+    Number(String),
+    Bool(bool),
+    Text(String),
+    Array(Vec<PhhValue>),
+}
+
+impl PhhValue {
+    /// Amounts of an array of numbers, such as stacks or blinds, `None` unless every number is
+    /// a whole number of chips of `denomination`
+    fn chips(&self, denomination: &Denomination) -> Option<Vec<Chips>> {
+        self.numbers()?
+            .into_iter()
+            .map(|number| denomination.parse(number))
+            .collect()
+    }
+
+    fn numbers(&self) -> Option<Vec<&str>> {
+        match self {
+            PhhValue::Array(values) => values
+                .iter()
+                .map(|value| match value {
+                    PhhValue::Number(number) => Some(number.as_str()),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+
+    fn texts(&self) -> Option<Vec<&str>> {
+        match self {
+            PhhValue::Array(values) => values
+                .iter()
+                .map(|value| match value {
+                    PhhValue::Text(text) => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the value at the start of `text`, returning it with the rest of the text
+fn parse_value(text: &str) -> Option<(PhhValue, &str)> {
+    let text = text.trim_start();
+    if let Some(rest) = text.strip_prefix('"') {
+        let end = rest.find('"')?;
+        return Some((PhhValue::Text(rest[..end].to_string()), &rest[end + 1..]));
+    }
+    if let Some(mut rest) = text.strip_prefix('[') {
+        let mut values = Vec::new();
+        loop {
+            rest = rest.trim_start();
+            if let Some(after) = rest.strip_prefix(']') {
+                return Some((PhhValue::Array(values), after));
+            }
+            let (value, after) = parse_value(rest)?;
+            values.push(value);
+            rest = after.trim_start();
+            rest = rest.strip_prefix(',').unwrap_or(rest);
+        }
+    }
+    let end = text
+        .find(|c: char| c == ',' || c == ']' || c.is_whitespace())
+        .unwrap_or(text.len());
+    let value = match &text[..end] {
+        "true" => PhhValue::Bool(true),
+        "false" => PhhValue::Bool(false),
+        number => {
+            let number = number.replace('_', "");
+            number.parse::<f64>().ok()?;
+            PhhValue::Number(number)
+        }
+    };
+    Some((value, &text[end..]))
+}
+
+/// Digits after the decimal point of an amount, trailing zeros aside
+fn decimals(amount: &str) -> usize {
+    amount
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.trim_end_matches('0').len())
+}
+
+/// Name, first line and fields of a hand of a PHH file
+type Section = (String, usize, Vec<(String, PhhValue)>);
+
+/// Removes a `#` comment, leaving the ones inside strings alone
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Reads the hands of a PHH (Poker Hand History) file, either a single `.phh` hand or a `.phhs`
+/// file with one `[section]` per hand
+///
+/// Only no-limit hold'em hands without antes or straddles, the big blind sitting right after the
+/// small blind, can be replayed, other hands give an `Unsupported` error. A chip is the smallest
+/// fraction the amounts of a hand are written in, such as a cent for `0.01`, which the
+/// denomination of its settings records.
+pub fn parse_phh(text: &str) -> Result<Vec<ParsedHand>, HistoryError> {
+    let mut sections: Vec<Section> = Vec::new();
+    let mut pending = String::new();
+    let mut pending_line = 0;
+
+    for (i, line) in text.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        if pending.is_empty() {
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with('[') && !line.contains('=') {
+                let name = line.trim_matches(|c| c == '[' || c == ']').to_string();
+                sections.push((name, i + 1, Vec::new()));
+                continue;
+            }
+            pending_line = i + 1;
+        }
+        pending.push_str(line);
+        pending.push(' ');
+
+        // arrays can span several lines
+        let (key, value) = pending.split_once('=').ok_or_else(|| HistoryError::Parse {
+            line: pending_line,
+            message: "expected a key and a value".to_string(),
+        })?;
+        let opened = value.matches('[').count();
+        if opened > value.matches(']').count() {
+            continue;
+        }
+        let (value, _) = parse_value(value).ok_or_else(|| HistoryError::Parse {
+            line: pending_line,
+            message: format!("invalid value for {}", key.trim()),
+        })?;
+        if sections.is_empty() {
+            sections.push((String::new(), pending_line, Vec::new()));
+        }
+        // a section was just pushed when there was none
+        sections
+            .last_mut()
+            .unwrap()
+            .2
+            .push((key.trim().to_string(), value));
+        pending.clear();
+    }
+    if !pending.is_empty() {
+        return Err(HistoryError::Parse {
+            line: pending_line,
+            message: "unterminated value".to_string(),
+        });
+    }
+
+    sections
+        .iter()
+        .enumerate()
+        .map(|(i, (name, line, fields))| {
+            let id = if name.is_empty() {
+                (i + 1).to_string()
+            } else {
+                name.clone()
+            };
+            parse_fields(id, *line, fields)
+        })
+        .collect()
+}
+
+fn parse_fields(
+    id: String,
+    line: usize,
+    fields: &[(String, PhhValue)],
+) -> Result<ParsedHand, HistoryError> {
+    let error = |message: String| HistoryError::Parse { line, message };
+    let field = |key: &str| {
+        fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+    };
+
+    if field("variant") != Some(&PhhValue::Text("NT".to_string())) {
+        return Err(HistoryError::Unsupported(format!(
+            "hand {id} is not no-limit hold'em"
+        )));
+    }
+    let actions_field = field("actions")
+        .and_then(PhhValue::texts)
+        .ok_or_else(|| error("missing or invalid actions".to_string()))?;
+
+    // a chip is the smallest fraction any amount of the hand is written in
+    let bet_amounts = actions_field.iter().filter_map(|text| {
+        match strip_comment(text).split_whitespace().collect::<Vec<_>>()[..] {
+            [_, "cbr", amount] => Some(amount),
+            _ => None,
+        }
+    });
+    let decimals = [
+        "starting_stacks",
+        "blinds_or_straddles",
+        "antes",
+        "finishing_stacks",
+    ]
+    .into_iter()
+    .filter_map(|key| field(key).and_then(PhhValue::numbers))
+    .flatten()
+    .chain(bet_amounts)
+    .map(decimals)
+    .max()
+    .unwrap_or(0);
+    let denomination = Denomination {
+        symbol: String::new(),
+        decimals: u32::try_from(decimals).unwrap_or(u32::MAX),
+    };
+    let chips = |key: &str| {
+        field(key)
+            .and_then(|value| value.chips(&denomination))
+            .ok_or_else(|| error(format!("missing or invalid {key}")))
+    };
+    let stacks = chips("starting_stacks")?;
+    let n_players = stacks.len();
+    if field("antes").is_some() && chips("antes")?.iter().any(|ante| !ante.is_zero()) {
+        return Err(HistoryError::Unsupported(format!("hand {id} has antes")));
+    }
+
+    // heads-up the button is the second player and posts the small blind
+    let mut blinds = chips("blinds_or_straddles")?;
+    blinds.resize(n_players, Chips::ZERO);
+    if n_players == 2 {
+        blinds.reverse();
+    }
+    let posted: Vec<usize> = (0..n_players)
+        .filter(|&seat| !blinds[seat].is_zero())
+        .collect();
+    let (sb_seat, bb_seat) = match posted[..] {
+        [first, second] if blinds[first] <= blinds[second] => (first, second),
+        [first, second] => (second, first),
+        _ => {
+            return Err(HistoryError::Unsupported(format!(
+                "hand {id} does not have exactly two blinds"
+            )));
+        }
+    };
+    let next_live = (1..n_players)
+        .map(|i| (sb_seat + i) % n_players)
+        .find(|&seat| !stacks[seat].is_zero());
+    if next_live != Some(bb_seat) {
+        return Err(HistoryError::Unsupported(format!(
+            "hand {id} has the big blind out of position"
+        )));
+    }
+
+    let mut setup = HandSetup {
+        stacks: stacks.clone(),
+        sb_seat,
+        hands: vec![None; n_players],
+        board: Vec::new(),
+    };
+    let mut remaining = stacks.clone();
+    let mut bets = vec![Chips::ZERO; n_players];
+    for seat in [sb_seat, bb_seat] {
+        let blind = blinds[seat].min(remaining[seat]);
+        remaining[seat] -= blind;
+        bets[seat] = blind;
+    }
+
+    let player = |token: &str| {
+        token
+            .strip_prefix('p')
+            .and_then(|number| number.parse::<usize>().ok())
+            .filter(|&number| number >= 1 && number <= n_players)
+            .map(|number| number - 1)
+            .ok_or_else(|| error(format!("invalid player {token}")))
+    };
+
+    let mut actions = Vec::new();
+    for text in actions_field {
+        let words: Vec<&str> = strip_comment(text).split_whitespace().collect();
+        let invalid = || error(format!("invalid action {text}"));
+        match words[..] {
+            ["d", "dh", seat, cards] => {
+                setup.hands[player(seat)?] = parse_hand(cards);
+            }
+            ["d", "db", cards] => {
+                setup.board.extend(parse_cards(cards).ok_or_else(invalid)?);
+                bets.iter_mut().for_each(|bet| *bet = Chips::ZERO);
+            }
+            [seat, "sm", cards] => {
+                let seat = player(seat)?;
+                setup.hands[seat] = parse_hand(cards).or(setup.hands[seat]);
+            }
+            [_, "sm"] => {}
+            [seat, verb, ref amount @ ..] => {
+                let seat = player(seat)?;
+                let max_bet = bets.iter().copied().max().unwrap_or_default();
+                let to_call = (max_bet - bets[seat]).min(remaining[seat]);
+                let (action, chips) = match (verb, amount) {
+                    ("f", []) => (Action::Fold, Chips::ZERO),
+                    ("cc", []) if to_call.is_zero() => (Action::Check, Chips::ZERO),
+                    ("cc", []) => (Action::Call, to_call),
+                    ("cbr", [to]) => {
+                        let to = denomination.parse(to).ok_or_else(invalid)?;
+                        let raise = to.checked_sub(max_bet).ok_or_else(invalid)?;
+                        (Action::Raise(raise), to.saturating_sub(bets[seat]))
+                    }
+                    _ => {
+                        return Err(HistoryError::Unsupported(format!(
+                            "hand {id} has the action {text}"
+                        )));
+                    }
+                };
+                let chips = chips.min(remaining[seat]);
+                remaining[seat] -= chips;
+                bets[seat] += chips;
+                actions.push(ParsedAction {
+                    seat,
+                    action,
+                    amount: chips,
+                });
+            }
+            _ => return Err(invalid()),
+        }
+    }
+
+    let final_stacks = match field("finishing_stacks") {
+        Some(_) => Some(chips("finishing_stacks")?),
+        None => None,
+    };
+    Ok(ParsedHand {
+        id,
+        settings: Settings {
+            n_players,
+            initial_stack: stacks.iter().copied().max().unwrap_or_default(),
+            small_blind: blinds[sb_seat],
+            big_blind: blinds[bb_seat],
+            denomination,
+            ..Default::default()
+        },
+        setup,
+        actions,
+        pot: None,
+        rake: Chips::ZERO,
+        final_stacks,
+    })
+}
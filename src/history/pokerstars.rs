@@ -0,0 +1,366 @@
+use crate::history::*;
+
+/// Reads every hand of a PokerStars hand history file
+///
+/// Only hold'em hands, no-limit, pot-limit or fixed-limit with at most four bets a street, with
+/// a small and a big blind and no antes can be replayed, other hands give an `Unsupported`
+/// error. Cash game amounts are read in cents.
+pub fn parse_pokerstars(text: &str) -> Result<Vec<ParsedHand>, HistoryError> {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}').trim_end())
+        .collect();
+    let starts: Vec<usize> = (0..lines.len())
+        .filter(|&i| lines[i].starts_with("PokerStars ") && lines[i].contains('#'))
+        .collect();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(lines.len());
+            parse_hand_lines(&lines[start..end], start + 1)
+        })
+        .collect()
+}
+
+/// Seat of a player and the name it goes by
+struct Player {
+    name: String,
+    seat: usize,
+}
+
+struct HandParser {
+    first_line: usize,
+    scale: usize,
+    players: Vec<Player>,
+    bets: Vec<Chips>,
+    committed: Vec<Chips>,
+}
+
+impl HandParser {
+    fn error(&self, offset: usize, message: impl Into<String>) -> HistoryError {
+        HistoryError::Parse {
+            line: self.first_line + offset,
+            message: message.into(),
+        }
+    }
+
+    /// Chips of an amount such as `1,500`, `$0.25` or `€2 EUR`
+    fn amount(&self, text: &str) -> Option<Chips> {
+        let text: String = text
+            .chars()
+            .filter(|c| c.is_ascii_digit() || *c == '.')
+            .collect();
+        if text.contains('.') {
+            let value: f64 = text.parse().ok()?;
+            Some(Chips::new((value * self.scale as f64).round() as usize))
+        } else {
+            Chips::new(text.parse().ok()?).checked_mul(self.scale)
+        }
+    }
+
+    /// Player a line is about and the rest of the line, after `name: ` or `name `
+    fn player<'a>(&self, line: &'a str, separator: &str) -> Option<(usize, &'a str)> {
+        self.players
+            .iter()
+            .filter(|player| line.starts_with(&format!("{}{separator}", player.name)))
+            .max_by_key(|player| player.name.len())
+            .map(|player| (player.seat, &line[player.name.len() + separator.len()..]))
+    }
+
+    fn seat(&self, name: &str) -> Option<usize> {
+        self.players
+            .iter()
+            .find(|player| player.name == name)
+            .map(|player| player.seat)
+    }
+
+    fn put_in(&mut self, seat: usize, amount: Chips) {
+        self.bets[seat] += amount;
+        self.committed[seat] += amount;
+    }
+}
+
+/// Cards of every bracket of a line, such as `*** TURN *** [Ad 3c 7c] [7h]`
+fn bracket_cards(text: &str) -> Option<Vec<Card>> {
+    let mut cards = Vec::new();
+    for part in text.split('[').skip(1) {
+        cards.extend(parse_cards(part.split(']').next()?)?);
+    }
+    Some(cards)
+}
+
+fn parse_hand_lines(lines: &[&str], first_line: usize) -> Result<ParsedHand, HistoryError> {
+    let header = lines[0];
+    let mut parser = HandParser {
+        first_line,
+        scale: if header.contains(['$', '€', '£']) {
+            100
+        } else {
+            1
+        },
+        players: Vec::new(),
+        bets: Vec::new(),
+        committed: Vec::new(),
+    };
+
+    let id = header
+        .split('#')
+        .nth(1)
+        .and_then(|rest| rest.split(':').next())
+        .unwrap_or_default()
+        .to_string();
+    let betting = if header.contains("Hold'em No Limit") {
+        BettingStructure::NoLimit
+    } else if header.contains("Hold'em Pot Limit") {
+        BettingStructure::PotLimit
+    } else if let Some((_, stakes)) = header.split_once("Hold'em Limit (") {
+        // fixed-limit stakes are the small and the big bet
+        let bets = stakes
+            .split(')')
+            .next()
+            .and_then(|stakes| stakes.split_once('/'))
+            .and_then(|(small, big)| Some((parser.amount(small)?, parser.amount(big)?)))
+            .ok_or_else(|| parser.error(0, "invalid stakes"))?;
+        BettingStructure::FixedLimit {
+            small_bet: bets.0,
+            big_bet: bets.1,
+            max_bets: 4,
+        }
+    } else {
+        return Err(HistoryError::Unsupported(format!(
+            "hand {id} is not hold'em"
+        )));
+    };
+
+    let mut n_players = 0;
+    let mut stacks: Vec<(usize, Chips)> = Vec::new();
+    let mut i = 1;
+    while i < lines.len() {
+        let line = lines[i];
+        if let Some(table) = line.strip_prefix("Table ") {
+            n_players = table
+                .split_whitespace()
+                .find_map(|word| word.strip_suffix("-max")?.parse().ok())
+                .unwrap_or(0);
+        } else if let Some(seat_line) = line.strip_prefix("Seat ") {
+            let (number, rest) = seat_line
+                .split_once(": ")
+                .ok_or_else(|| parser.error(i, "seat line without a player"))?;
+            let seat = number
+                .parse::<usize>()
+                .ok()
+                .filter(|&seat| seat > 0)
+                .ok_or_else(|| parser.error(i, "invalid seat number"))?
+                - 1;
+            let open = rest
+                .rfind(" (")
+                .ok_or_else(|| parser.error(i, "seat line without a stack"))?;
+            let chips = rest[open + 2..]
+                .split(" in chips")
+                .next()
+                .and_then(|chips| parser.amount(chips))
+                .ok_or_else(|| parser.error(i, "invalid stack"))?;
+            parser.players.push(Player {
+                name: rest[..open].to_string(),
+                seat,
+            });
+            if !rest.contains("is sitting out") {
+                stacks.push((seat, chips));
+            }
+        } else if !stacks.is_empty() {
+            break;
+        }
+        i += 1;
+    }
+
+    let n_players = parser
+        .players
+        .iter()
+        .map(|player| player.seat + 1)
+        .max()
+        .unwrap_or(0)
+        .max(n_players);
+    let mut setup = HandSetup {
+        stacks: vec![Chips::ZERO; n_players],
+        hands: vec![None; n_players],
+        ..Default::default()
+    };
+    for &(seat, chips) in &stacks {
+        setup.stacks[seat] = chips;
+    }
+    parser.bets = vec![Chips::ZERO; n_players];
+    parser.committed = vec![Chips::ZERO; n_players];
+
+    let mut blinds = [None, None];
+    let mut actions = Vec::new();
+    let mut collected = vec![Chips::ZERO; n_players];
+    let mut pot = None;
+    let mut rake = Chips::ZERO;
+    let mut summary = false;
+
+    for (offset, &line) in lines.iter().enumerate().skip(i) {
+        if line.starts_with("*** SUMMARY") {
+            summary = true;
+        } else if line.starts_with("*** FLOP")
+            || line.starts_with("*** TURN")
+            || line.starts_with("*** RIVER")
+        {
+            setup.board =
+                bracket_cards(line).ok_or_else(|| parser.error(offset, "invalid board"))?;
+            parser.bets.iter_mut().for_each(|bet| *bet = Chips::ZERO);
+        } else if let Some(rest) = line.strip_prefix("Dealt to ") {
+            if let Some((seat, cards)) = parser.player(rest, " ") {
+                let cards =
+                    bracket_cards(cards).ok_or_else(|| parser.error(offset, "invalid cards"))?;
+                if let &[first, second] = cards.as_slice() {
+                    setup.hands[seat] = Some(PlayerHand::new(first, second));
+                }
+            }
+        } else if let Some(rest) = line.strip_prefix("Total pot ") {
+            pot = rest
+                .split_whitespace()
+                .next()
+                .and_then(|p| parser.amount(p));
+            rake = rest
+                .split("Rake ")
+                .nth(1)
+                .and_then(|r| parser.amount(r.split_whitespace().next()?))
+                .unwrap_or_default();
+        } else if summary {
+            // mucked and shown hands of the summary seat lines
+            for keyword in ["showed [", "mucked ["] {
+                if let Some(position) = line.find(keyword)
+                    && let Some((seat, _)) = line
+                        .strip_prefix("Seat ")
+                        .and_then(|rest| rest.split_once(": "))
+                        .and_then(|(_, rest)| parser.player(rest, " "))
+                    && let Some(hand) = line[position + keyword.len()..]
+                        .split(']')
+                        .next()
+                        .and_then(parse_hand)
+                {
+                    setup.hands[seat] = Some(hand);
+                }
+            }
+        } else if let Some((seat, rest)) = parser.player(line, " collected ") {
+            collected[seat] += rest
+                .split_whitespace()
+                .next()
+                .and_then(|amount| parser.amount(amount))
+                .ok_or_else(|| parser.error(offset, "invalid amount"))?;
+        } else if let Some((seat, rest)) = parser.player(line, ": ") {
+            let words: Vec<&str> = rest.split_whitespace().collect();
+            let amount = |index: usize| {
+                words
+                    .get(index)
+                    .and_then(|word| parser.amount(word))
+                    .ok_or_else(|| parser.error(offset, "invalid amount"))
+            };
+            let max_bet = parser.bets.iter().copied().max().unwrap_or_default();
+            let action = match words.as_slice() {
+                ["posts", "small", "blind", ..] => {
+                    let chips = amount(3)?;
+                    blinds[0] = Some((seat, chips));
+                    parser.put_in(seat, chips);
+                    None
+                }
+                ["posts", "big", "blind", ..] => {
+                    let chips = amount(3)?;
+                    blinds[1] = Some((seat, chips));
+                    parser.put_in(seat, chips);
+                    None
+                }
+                ["posts", ..] => {
+                    return Err(HistoryError::Unsupported(format!(
+                        "hand {id} has antes or extra blinds"
+                    )));
+                }
+                ["folds", ..] => Some((Action::Fold, Chips::ZERO)),
+                ["checks", ..] => Some((Action::Check, Chips::ZERO)),
+                ["calls", ..] => Some((Action::Call, amount(1)?)),
+                ["bets", ..] => {
+                    let chips = amount(1)?;
+                    Some((Action::Raise(chips), chips))
+                }
+                ["raises", _, "to", ..] => {
+                    let to = amount(3)?;
+                    let raise = to
+                        .checked_sub(max_bet)
+                        .ok_or_else(|| parser.error(offset, "raise below the bet"))?;
+                    let chips = to
+                        .checked_sub(parser.bets[seat])
+                        .ok_or_else(|| parser.error(offset, "raise below the bet"))?;
+                    Some((Action::Raise(raise), chips))
+                }
+                ["shows", ..] => {
+                    if let Some(hand) = bracket_cards(rest).and_then(|cards| match cards[..] {
+                        [first, second] => Some(PlayerHand::new(first, second)),
+                        _ => None,
+                    }) {
+                        setup.hands[seat] = Some(hand);
+                    }
+                    None
+                }
+                _ => None,
+            };
+            if let Some((action, chips)) = action {
+                parser.put_in(seat, chips);
+                actions.push(ParsedAction {
+                    seat,
+                    action,
+                    amount: chips,
+                });
+            }
+        } else if let Some(rest) = line.strip_prefix("Uncalled bet (") {
+            let amount = rest
+                .split(')')
+                .next()
+                .and_then(|amount| parser.amount(amount))
+                .ok_or_else(|| parser.error(offset, "invalid amount"))?;
+            if let Some(seat) = rest
+                .split_once("returned to ")
+                .and_then(|(_, name)| parser.seat(name))
+            {
+                collected[seat] += amount;
+            }
+        }
+    }
+
+    let [Some((sb_seat, small_blind)), Some((bb_seat, big_blind))] = blinds else {
+        return Err(HistoryError::Unsupported(format!(
+            "hand {id} does not have both blinds"
+        )));
+    };
+    setup.sb_seat = sb_seat;
+    let next_live = (1..n_players)
+        .map(|i| (sb_seat + i) % n_players)
+        .find(|&seat| !setup.stacks[seat].is_zero());
+    if next_live != Some(bb_seat) {
+        return Err(HistoryError::Unsupported(format!(
+            "hand {id} has the big blind out of position"
+        )));
+    }
+
+    let final_stacks = (0..n_players)
+        .map(|seat| {
+            setup.stacks[seat] - parser.committed[seat].min(setup.stacks[seat]) + collected[seat]
+        })
+        .collect();
+    Ok(ParsedHand {
+        id,
+        settings: Settings {
+            n_players,
+            initial_stack: setup.stacks.iter().copied().max().unwrap_or_default(),
+            small_blind,
+            big_blind,
+            betting,
+            ..Default::default()
+        },
+        setup,
+        actions,
+        pot,
+        rake,
+        final_stacks: Some(final_stacks),
+    })
+}
@@ -0,0 +1,208 @@
+use crate::history::*;
+
+/// Replays a parsed hand through a `Game`, checking the chips it moves against the history
+///
+/// Every action has to come from the seat the game expects and put in the recorded amount, and
+/// the pot has to match. Final stacks are compared when every hand going to showdown is known,
+/// since unknown cards are dealt at random from `seed`, with the recorded rake taken out of the
+/// seats that won chips.
+pub fn replay(hand: &ParsedHand, seed: u64) -> Result<HandHistory, HistoryError> {
+    let mismatch = |message: String| Err(HistoryError::Mismatch(message));
+
+    let mut game = Game::from_setup(hand.settings.clone(), &hand.setup, seed)?;
+    for (i, parsed) in hand.actions.iter().enumerate() {
+        if game.hand_count() > 0 {
+            return mismatch(format!("hand over before action {}", i + 1));
+        }
+        if game.current_seat() != parsed.seat {
+            return mismatch(format!(
+                "action {} played by seat {} while seat {} is to act",
+                i + 1,
+                parsed.seat,
+                game.current_seat()
+            ));
+        }
+        if game.play_turn(parsed.action).is_err() {
+            return mismatch(format!(
+                "action {} ({:?}) is not legal",
+                i + 1,
+                parsed.action
+            ));
+        }
+    }
+    let history = match game.last_hand_history() {
+        Some(history) if game.hand_count() > 0 => history.clone(),
+        _ => return mismatch("hand not over after the last action".to_string()),
+    };
+
+    for (i, (played, parsed)) in history.actions.iter().zip(&hand.actions).enumerate() {
+        if played.amount != parsed.amount {
+            return mismatch(format!(
+                "action {} put in {} chips instead of {}",
+                i + 1,
+                played.amount,
+                parsed.amount
+            ));
+        }
+    }
+
+    let committed = history.committed();
+    let uncalled = history
+        .uncalled_bet()
+        .map_or(Chips::ZERO, |(_, amount)| amount);
+    let pot = committed.iter().sum::<Chips>() - uncalled;
+    if let Some(expected) = hand.pot.filter(|&expected| expected != pot) {
+        return mismatch(format!("pot of {pot} instead of {expected}"));
+    }
+
+    let hidden = history
+        .shown_hands
+        .iter()
+        .any(|(seat, _)| hand.setup.hands.get(*seat).copied().flatten().is_none());
+    if let Some(expected) = &hand.final_stacks
+        && !hidden
+    {
+        let stacks: Vec<Chips> = (0..history.stacks.len())
+            .map(|seat| history.stacks[seat] - committed[seat] + history.awards[seat])
+            .collect();
+        // the history does not say which pot the rake came out of, only that it came out of awards
+        let raked: Option<Vec<Chips>> = stacks
+            .iter()
+            .zip(expected)
+            .map(|(&stack, &expected)| stack.checked_sub(expected))
+            .collect();
+        let valid = raked.is_some_and(|raked| {
+            raked
+                .iter()
+                .zip(&history.awards)
+                .all(|(raked, award)| raked <= award)
+                && raked.iter().sum::<Chips>() == hand.rake
+        });
+        if !valid {
+            return mismatch(format!(
+                "final stacks {stacks:?} less a rake of {} instead of {expected:?}",
+                hand.rake
+            ));
+        }
+    }
+
+    Ok(history)
+}
+
+#[test]
+fn replay_exported_and_phh_hands() {
+    let settings = Settings {
+        n_players: 4,
+        initial_stack: Chips::new(500),
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        ..Default::default()
+    };
+    let mut game = Game::with_seed(settings.clone(), 11).unwrap();
+    let mut agents: Vec<Box<dyn Agent>> = vec![
+        Box::new(RandomAgent::new(1)),
+        Box::new(CallingAgent),
+        Box::new(RandomAgent::new(2)),
+        Box::new(TightAggressiveAgent::new(3)),
+    ];
+    for _ in 0..20 {
+        if game.over() {
+            break;
+        }
+        play_hand(&mut game, &mut agents).unwrap();
+        let history = game.last_hand_history().unwrap();
+        let parsed = parse_pokerstars(&history.to_pokerstars(0)).unwrap();
+        let replayed = replay(&parsed[0], 0).unwrap();
+        assert_eq!(replayed.actions, history.actions);
+        assert_eq!(replayed.awards, history.awards);
+    }
+
+    // the replay does not know the rake rules, so the recorded rake is checked against the stacks
+    let raked = Settings {
+        n_players: 2,
+        rake: Rake {
+            basis_points: 1000,
+            ..Default::default()
+        },
+        ..settings
+    };
+    let mut game = Game::with_seed(raked, 3).unwrap();
+    let mut callers: Vec<Box<dyn Agent>> = vec![Box::new(CallingAgent), Box::new(CallingAgent)];
+    play_hand(&mut game, &mut callers).unwrap();
+    let text = game.last_hand_history().unwrap().to_pokerstars(0);
+    let parsed = parse_pokerstars(&text).unwrap();
+    assert_eq!(parsed[0].rake, Chips::new(2));
+    replay(&parsed[0], 0).unwrap();
+    let mut unraked = parsed[0].clone();
+    unraked.rake = Chips::ZERO;
+    assert!(matches!(
+        replay(&unraked, 0),
+        Err(HistoryError::Mismatch(_))
+    ));
+
+    let phh = r#"
+variant = "NT"
+antes = [0, 0, 0]
+blinds_or_straddles = [1, 2, 0]
+min_bet = 2
+starting_stacks = [200, 200, 200]
+actions = [
+  "d dh p1 AsKs",
+  "d dh p2 7h7d",
+  "d dh p3 ????",
+  "p3 f",
+  "p1 cbr 6",
+  "p2 cc",
+  "d db Jc3d5c",
+  "p1 cbr 10",
+  "p2 cbr 40",
+  "p1 cc",
+  "d db 4h",
+  "p1 cc",
+  "p2 cc",
+  "d db Ah",
+  "p1 cbr 154",
+  "p2 cc",
+  "p1 sm AsKs",
+  "p2 sm 7h7d",  # aces beat sevens
+]
+finishing_stacks = [400, 0, 200]
+"#;
+    let parsed = parse_phh(phh).unwrap();
+    assert_eq!(parsed[0].actions.len(), 10);
+    assert!(replay(&parsed[0], 0).unwrap().showdown);
+
+    let mut wrong = parsed[0].clone();
+    wrong.final_stacks = Some(vec![Chips::new(200); 3]);
+    assert!(matches!(replay(&wrong, 0), Err(HistoryError::Mismatch(_))));
+
+    // cents are counted exactly, and amounts too fine for chips are refused
+    let cents = r#"
+variant = "NT"
+blinds_or_straddles = [0.01, 0.02, 0]
+starting_stacks = [2, 2, 2]
+actions = ["d dh p1 AsKs", "d dh p2 7h7d", "d dh p3 QcQd", "p3 f", "p1 cbr 0.06", "p2 f"]
+finishing_stacks = [2.02, 1.98, 2.00]
+"#;
+    let parsed = parse_phh(cents).unwrap();
+    let settings = &parsed[0].settings;
+    assert_eq!(settings.denomination.decimals, 2);
+    assert_eq!(
+        (settings.small_blind, settings.big_blind),
+        (Chips::new(1), Chips::new(2))
+    );
+    assert_eq!(parsed[0].actions[1].action, Action::Raise(Chips::new(4)));
+    replay(&parsed[0], 0).unwrap();
+    let tiny = cents.replace("0.06", "0.000000000000000000006");
+    assert!(matches!(parse_phh(&tiny), Err(HistoryError::Parse { .. })));
+
+    // the big blind must sit right after the small blind
+    let skipped = phh.replace(
+        "blinds_or_straddles = [1, 2, 0]",
+        "blinds_or_straddles = [1, 0, 2]",
+    );
+    assert!(matches!(
+        parse_phh(&skipped),
+        Err(HistoryError::Unsupported(_))
+    ));
+}
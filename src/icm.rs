@@ -0,0 +1,191 @@
+use crate::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Most seats with chips `icm_equities` computes exactly
+pub const ICM_EXACT_SEATS: usize = 20;
+
+/// Finishing orders `icm_equities` draws past `ICM_EXACT_SEATS` seats with chips
+pub const ICM_SAMPLES: usize = 100_000;
+
+/// Prize equity of every seat under the Malmuth-Harville model of the Independent Chip Model
+///
+/// `payouts` holds the prize of every place, the winner's first, places past its end paying
+/// nothing. A seat finishes first with a probability proportional to its stack, then the
+/// places that follow are drawn the same way among the seats left. Seats without chips get
+/// nothing, the places going to the seats with chips. The cost doubles with every seat with
+/// chips, so past `ICM_EXACT_SEATS` of them the equities are estimated by
+/// `icm_equities_sampled` from `ICM_SAMPLES` orders of a fixed seed instead.
+pub fn icm_equities(stacks: &[Chips], payouts: &[f64]) -> Vec<f64> {
+    let seats: Vec<usize> = (0..stacks.len())
+        .filter(|&seat| !stacks[seat].is_zero())
+        .collect();
+    let places = payouts.len().min(seats.len());
+    let mut equities = vec![0.0; stacks.len()];
+    if places == 0 {
+        return equities;
+    }
+    if seats.len() > ICM_EXACT_SEATS {
+        return icm_equities_sampled(stacks, payouts, ICM_SAMPLES, 0);
+    }
+
+    // probability that the seats of every subset are the ones left to place, largest first
+    let full = (1usize << seats.len()) - 1;
+    let mut probabilities = vec![0.0; full + 1];
+    probabilities[full] = 1.0;
+    for left in (1..=full).rev() {
+        let probability = probabilities[left];
+        let place = seats.len() - left.count_ones() as usize;
+        if probability == 0.0 || place >= places {
+            continue;
+        }
+        let chips: Chips = (0..seats.len())
+            .filter(|&i| left & (1 << i) != 0)
+            .map(|i| stacks[seats[i]])
+            .sum();
+        for (i, &seat) in seats.iter().enumerate() {
+            if left & (1 << i) != 0 {
+                let finishes = probability * stacks[seat].as_f64() / chips.as_f64();
+                equities[seat] += finishes * payouts[place];
+                probabilities[left & !(1 << i)] += finishes;
+            }
+        }
+    }
+    equities
+}
+
+/// Estimate of `icm_equities` from `samples` finishing orders drawn under the same model
+///
+/// Each seat gets an exponential finishing time of rate its stack, the seat with the shortest
+/// time winning, which draws orders exactly as Malmuth-Harville does. The cost grows linearly
+/// with the seats, for fields too large for the exact computation.
+pub fn icm_equities_sampled(
+    stacks: &[Chips],
+    payouts: &[f64],
+    samples: usize,
+    seed: u64,
+) -> Vec<f64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let seats: Vec<usize> = (0..stacks.len())
+        .filter(|&seat| !stacks[seat].is_zero())
+        .collect();
+    let mut equities = vec![0.0; stacks.len()];
+    let mut times: Vec<(f64, usize)> = Vec::with_capacity(seats.len());
+    for _ in 0..samples {
+        times.clear();
+        times.extend(seats.iter().map(|&seat| {
+            let uniform: f64 = rng.random();
+            (-(1.0 - uniform).ln() / stacks[seat].as_f64(), seat)
+        }));
+        times.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for (&(_, seat), payout) in times.iter().zip(payouts) {
+            equities[seat] += payout;
+        }
+    }
+    for equity in &mut equities {
+        *equity /= samples.max(1) as f64;
+    }
+    equities
+}
+
+impl Game {
+    /// Change of the prize equity of every seat over the last finished hand
+    ///
+    /// Equities are the ones of `icm_equities` for the places still to be decided when the hand
+    /// started. Seats busted by the hand get the prizes of the places they finished in, seats
+    /// that started with more chips finishing ahead and equal stacks sharing their prizes.
+    pub fn icm_deltas(&self, payouts: &[f64]) -> Option<Vec<f64>> {
+        let before = &self.last_hand_history()?.stacks;
+        let after: Vec<Chips> = before
+            .iter()
+            .zip(&self.last_hand_result()?.deltas)
+            .map(|(&stack, &delta)| stack.saturating_add_signed(delta))
+            .collect();
+        let prize = |place: usize| payouts.get(place).copied().unwrap_or(0.0);
+
+        let equities_before = icm_equities(before, payouts);
+        let mut equities_after = icm_equities(&after, payouts);
+        let left = after.iter().filter(|stack| !stack.is_zero()).count();
+        let busted: Vec<usize> = (0..after.len())
+            .filter(|&seat| !before[seat].is_zero() && after[seat].is_zero())
+            .collect();
+        for &seat in &busted {
+            let ahead = busted
+                .iter()
+                .filter(|&&other| before[other] > before[seat])
+                .count();
+            let tied = busted
+                .iter()
+                .filter(|&&other| before[other] == before[seat])
+                .count();
+            let first = left + ahead;
+            equities_after[seat] = (first..first + tied).map(prize).sum::<f64>() / tied as f64;
+        }
+        Some(
+            equities_after
+                .iter()
+                .zip(&equities_before)
+                .map(|(after, before)| after - before)
+                .collect(),
+        )
+    }
+}
+
+#[test]
+fn icm() {
+    let payouts = [50.0, 30.0, 20.0];
+    let close = |a: &[f64], b: &[f64], tolerance: f64| {
+        a.iter().zip(b).all(|(a, b)| (a - b).abs() < tolerance)
+    };
+
+    // equal stacks share the prizes, a seat without chips gets nothing
+    let chips = |stacks: &[usize]| stacks.iter().copied().map(Chips::new).collect::<Vec<_>>();
+    let equities = icm_equities(&chips(&[100, 100, 100, 0]), &payouts);
+    assert!(close(
+        &equities,
+        &[100.0 / 3.0, 100.0 / 3.0, 100.0 / 3.0, 0.0],
+        1e-9
+    ));
+
+    // first place by chips, then second place among the seats left
+    let equities = icm_equities(&chips(&[50, 30, 20]), &payouts);
+    let first = 0.5;
+    let second = 0.3 * 0.5 / 0.7 + 0.2 * 0.5 / 0.8;
+    let third = 1.0 - first - second;
+    assert!((equities[0] - (50.0 * first + 30.0 * second + 20.0 * third)).abs() < 1e-9);
+    assert!((equities.iter().sum::<f64>() - 100.0).abs() < 1e-9);
+
+    let stacks = chips(&[400, 300, 200, 100, 50]);
+    let sampled = icm_equities_sampled(&stacks, &payouts, 200_000, 1);
+    assert!(close(&sampled, &icm_equities(&stacks, &payouts), 0.3));
+
+    // too many seats for the exact computation, the equities are sampled
+    let field = vec![Chips::new(100); 64];
+    let equities = icm_equities(&field, &payouts);
+    assert!(close(&equities, &[100.0 / 64.0; 64], 0.1));
+    assert!((equities.iter().sum::<f64>() - 100.0).abs() < 1e-9);
+
+    // the winner of a three way all-in takes first place, the two busted seats share the others
+    let settings = Settings {
+        n_players: 3,
+        initial_stack: Chips::new(100),
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        ..Default::default()
+    };
+    let mut game = Game::with_seed(settings, 2).unwrap();
+    game.play_turn(Action::Raise(Chips::new(90))).unwrap();
+    game.play_turn(Action::Call).unwrap();
+    game.play_turn(Action::Call).unwrap();
+    assert_eq!(
+        game.last_hand_result().unwrap().deltas,
+        vec![-100, -100, 200]
+    );
+    let deltas = game.icm_deltas(&payouts).unwrap();
+    let share = 100.0 / 3.0;
+    assert!(close(
+        &deltas,
+        &[25.0 - share, 25.0 - share, 50.0 - share],
+        1e-9
+    ));
+}
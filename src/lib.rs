@@ -1,13 +1,36 @@
+mod agent;
+mod aivat;
+mod arena;
+mod bots;
 mod card;
+mod cash;
+mod chips;
+mod encoder;
+mod event;
 mod game;
-mod player;
+mod history;
+mod icm;
 pub mod pygame;
 mod round;
+mod solver;
+mod tournament;
 mod turn;
 
-use game::*;
-use player::*;
+pub use agent::*;
+pub use aivat::*;
+pub use arena::*;
+pub use bots::*;
+pub use card::*;
+pub use cash::*;
+pub use chips::*;
+pub use encoder::*;
+pub use event::*;
+pub use game::*;
+pub use history::*;
+pub use icm::*;
 use pygame::*;
+pub use solver::*;
+pub use tournament::*;
 
 use pyo3::prelude::*;
 use pyo3_stub_gen::define_stub_info_gatherer;
@@ -16,6 +39,20 @@ use pyo3_stub_gen::define_stub_info_gatherer;
 fn pokr(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyGame>()?;
     m.add_class::<PySettings>()?;
+    m.add_class::<PyBettingStructure>()?;
+    m.add_class::<PyRake>()?;
     m.add_class::<PyAction>()?;
+    m.add_class::<PyCard>()?;
+    m.add_class::<PyPlayerHand>()?;
+    m.add_class::<PyBoard>()?;
+    m.add_class::<PyPokerHand>()?;
+    m.add_class::<PyHandType>()?;
+    m.add_class::<PyRound>()?;
+    m.add_class::<PyObservation>()?;
+    m.add_class::<PyPokerEnv>()?;
+    m.add_class::<PyAgentIter>()?;
+    m.add_class::<PyPokerGymEnv>()?;
+    m.add_class::<PyVecGame>()?;
+    add_exceptions(m)?;
     Ok(())
 }
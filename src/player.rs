@@ -1,13 +0,0 @@
-use crate::*;
-
-#[derive(Clone, Copy)]
-pub struct Player {}
-
-impl Player {
-    pub fn new() -> Self {
-        Player {}
-    }
-    pub fn choose_action(&mut self) -> Action {
-        Action::Check
-    }
-}
@@ -0,0 +1,301 @@
+use crate::*;
+
+use pyo3::exceptions::PyIndexError;
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::*;
+
+/// Card built from its text, such as `As` or `Td`
+#[gen_stub_pyclass]
+#[pyclass(eq, ord, hash, frozen)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PyCard {
+    pub(crate) card: Card,
+}
+
+impl Ord for PyCard {
+    /// By value, then by suit so that only equal cards compare equal
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let key = |card: &Card| (card.value.number_value(), card.index());
+        key(&self.card).cmp(&key(&other.card))
+    }
+}
+
+impl PartialOrd for PyCard {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyCard {
+    #[new]
+    pub fn new(text: &str) -> PyResult<Self> {
+        Ok(PyCard {
+            card: text.parse()?,
+        })
+    }
+
+    /// Card of index `0..52`, see `index`
+    #[staticmethod]
+    pub fn from_index(index: usize) -> PyResult<Self> {
+        if index >= 52 {
+            return Err(PyIndexError::new_err(format!("no card has index {index}")));
+        }
+        Ok(PyCard {
+            card: Card::from_index(index),
+        })
+    }
+
+    /// 2 to 14 for an ace
+    #[getter]
+    pub fn value(&self) -> PyResult<usize> {
+        Ok(self.card.value.number_value())
+    }
+
+    /// One of `s`, `h`, `d` and `c`
+    #[getter]
+    pub fn suit(&self) -> PyResult<String> {
+        Ok(self.card.suit.to_string())
+    }
+
+    #[getter]
+    pub fn index(&self) -> PyResult<usize> {
+        Ok(self.card.index())
+    }
+
+    pub fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("PyCard('{}')", self.card))
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.card.to_string())
+    }
+}
+
+/// Two hole cards, equal whatever their order
+#[gen_stub_pyclass]
+#[pyclass(eq, hash, frozen)]
+#[derive(Clone, Copy)]
+pub struct PyPlayerHand {
+    pub(crate) hand: PlayerHand,
+}
+
+impl PyPlayerHand {
+    fn key(&self) -> (usize, usize) {
+        let [first, second] = self.cards_array();
+        (
+            first.index().min(second.index()),
+            first.index().max(second.index()),
+        )
+    }
+
+    fn cards_array(&self) -> [Card; 2] {
+        let mut cards = self.hand.cards().copied();
+        // a player hand always holds two cards
+        [cards.next().unwrap(), cards.next().unwrap()]
+    }
+}
+
+impl PartialEq for PyPlayerHand {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for PyPlayerHand {}
+
+impl std::hash::Hash for PyPlayerHand {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyPlayerHand {
+    #[new]
+    pub fn new(text: &str) -> PyResult<Self> {
+        match Card::parse_list(text)?[..] {
+            [first, second] if first != second => Ok(PyPlayerHand {
+                hand: PlayerHand::new(first, second),
+            }),
+            _ => Err(CardError::InvalidCard(text.to_string()).into()),
+        }
+    }
+
+    pub fn cards(&self) -> PyResult<Vec<PyCard>> {
+        Ok(self.hand.cards().map(|&card| PyCard { card }).collect())
+    }
+
+    pub fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("PyPlayerHand('{}')", self.__str__()?))
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.hand.cards().map(Card::to_string).collect())
+    }
+}
+
+/// Community cards, in the order they were dealt
+#[gen_stub_pyclass]
+#[pyclass(eq, hash, frozen)]
+#[derive(Clone, PartialEq, Eq)]
+pub struct PyBoard {
+    pub(crate) board: Board,
+}
+
+impl std::hash::Hash for PyBoard {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        for card in self.board.cards() {
+            card.hash(state);
+        }
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyBoard {
+    #[new]
+    #[pyo3(signature = (text=""))]
+    pub fn new(text: &str) -> PyResult<Self> {
+        let mut board = Board::new();
+        for card in Card::parse_list(text)? {
+            board.add_card(card)?;
+        }
+        Ok(PyBoard { board })
+    }
+
+    /// Board with one more card, raises `BoardOverflowError` past five cards
+    pub fn with_card(&self, card: &PyCard) -> PyResult<PyBoard> {
+        let mut board = self.board.clone();
+        board.add_card(card.card)?;
+        Ok(PyBoard { board })
+    }
+
+    pub fn cards(&self) -> PyResult<Vec<PyCard>> {
+        Ok(self.board.cards().map(|&card| PyCard { card }).collect())
+    }
+
+    /// Best five card hand the hole cards make with the board, which needs three cards or more
+    pub fn best_poker_hand(&self, hand: &PyPlayerHand) -> PyResult<PyPokerHand> {
+        let cards: Vec<Card> = hand
+            .hand
+            .cards()
+            .chain(self.board.cards())
+            .copied()
+            .collect();
+        if cards.len() < 5 {
+            return Err(CardError::InvalidPokerHandCardCount.into());
+        }
+        Ok(PyPokerHand::from_hand(
+            self.board.best_poker_hand(&hand.hand)?,
+        ))
+    }
+
+    pub fn __len__(&self) -> PyResult<usize> {
+        Ok(self.board.card_count())
+    }
+
+    pub fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("PyBoard('{}')", self.__str__()?))
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.board.cards().map(Card::to_string).collect())
+    }
+}
+
+/// Five cards, comparing and hashing by strength so that hands of equal strength are equal
+#[gen_stub_pyclass]
+#[pyclass(eq, ord, hash, frozen)]
+#[derive(Clone)]
+pub struct PyPokerHand {
+    hand: PokerHand,
+    rank: HandRank,
+}
+
+impl PyPokerHand {
+    fn from_hand(hand: PokerHand) -> Self {
+        let rank = hand.rank();
+        PyPokerHand { hand, rank }
+    }
+}
+
+impl PartialEq for PyPokerHand {
+    fn eq(&self, other: &Self) -> bool {
+        self.rank == other.rank
+    }
+}
+
+impl Eq for PyPokerHand {}
+
+impl PartialOrd for PyPokerHand {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.rank.cmp(&other.rank))
+    }
+}
+
+impl std::hash::Hash for PyPokerHand {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.rank.hash(state);
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyPokerHand {
+    #[new]
+    pub fn new(text: &str) -> PyResult<Self> {
+        let cards = Card::parse_list(text)?;
+        Ok(Self::from_hand(PokerHand::new(cards.iter())?))
+    }
+
+    pub fn hand_type(&self) -> PyResult<PyHandType> {
+        Ok(self.rank.hand_type().into())
+    }
+
+    pub fn cards(&self) -> PyResult<Vec<PyCard>> {
+        Ok(self.hand.cards().map(|&card| PyCard { card }).collect())
+    }
+
+    pub fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("PyPokerHand('{}')", self.__str__()?))
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.hand.cards().map(Card::to_string).collect())
+    }
+}
+
+/// Hand types from the weakest to the strongest
+#[gen_stub_pyclass_enum]
+#[pyclass(eq, eq_int, ord, hash, frozen)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PyHandType {
+    HighCard,
+    Pair,
+    DoublePair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+impl From<HandType> for PyHandType {
+    fn from(hand_type: HandType) -> Self {
+        match hand_type {
+            HandType::HighCard => PyHandType::HighCard,
+            HandType::Pair => PyHandType::Pair,
+            HandType::DoublePair => PyHandType::DoublePair,
+            HandType::ThreeOfAKind => PyHandType::ThreeOfAKind,
+            HandType::Straight => PyHandType::Straight,
+            HandType::Flush => PyHandType::Flush,
+            HandType::FullHouse => PyHandType::FullHouse,
+            HandType::FourOfAKind => PyHandType::FourOfAKind,
+            HandType::StraightFlush => PyHandType::StraightFlush,
+        }
+    }
+}
@@ -0,0 +1,681 @@
+use crate::*;
+
+use super::errors::InvalidActionError;
+use numpy::PyArray1;
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+use pyo3_stub_gen::derive::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Observation, reward, termination, truncation and info of an agent
+type Last<'py> = (
+    Option<Bound<'py, PyDict>>,
+    f64,
+    bool,
+    bool,
+    Bound<'py, PyDict>,
+);
+type Step<'py> = (Bound<'py, PyDict>, f64, bool, bool, Bound<'py, PyDict>);
+
+/// One hand played with discrete actions: folding, checking or calling, one raise per pot
+/// fraction and going all-in, in this order
+pub(super) struct EnvCore {
+    pub(super) settings: Settings,
+    bets: BetAbstraction,
+    pub(super) encoder: ObservationEncoder,
+    pub(super) game: Game,
+    /// Hand count of the game when the hand started
+    hand_count: usize,
+    /// What every seat saw before the last action, kept to observe the hand once it ended
+    last_states: Vec<ObservableState>,
+    rng: StdRng,
+}
+
+impl EnvCore {
+    pub(super) fn new(
+        settings: &PySettings,
+        pot_fractions: Vec<f64>,
+        max_history: usize,
+    ) -> PyResult<Self> {
+        let settings = settings.settings.clone();
+        settings.validate()?;
+        if pot_fractions.iter().any(|&fraction| fraction <= 0.0) {
+            return Err(PyValueError::new_err("pot fractions must be positive"));
+        }
+        if settings.n_players == 2 && settings.initial_stack <= settings.small_blind {
+            return Err(PyValueError::new_err(
+                "the blinds would put both seats all-in in every hand",
+            ));
+        }
+        let mut core = EnvCore {
+            encoder: ObservationEncoder {
+                n_players: settings.n_players,
+                max_history,
+            },
+            game: Game::with_seed(settings.clone(), 0)?,
+            settings,
+            bets: BetAbstraction {
+                pot_fractions,
+                max_raises: usize::MAX,
+                all_in: true,
+            },
+            hand_count: 0,
+            last_states: Vec::new(),
+            rng: StdRng::from_os_rng(),
+        };
+        core.reset(None)?;
+        Ok(core)
+    }
+
+    /// Deals a new hand with full stacks and a random button, one that needs a decision
+    pub(super) fn reset(&mut self, seed: Option<u64>) -> PyResult<()> {
+        if let Some(seed) = seed {
+            self.rng = StdRng::seed_from_u64(seed);
+        }
+        let n = self.settings.n_players;
+        // the blinds can end a hand before anyone has a decision to make
+        loop {
+            let setup = HandSetup {
+                stacks: vec![self.settings.initial_stack; n],
+                sb_seat: self.rng.random_range(0..n),
+                ..Default::default()
+            };
+            self.game = Game::from_setup(self.settings.clone(), &setup, self.rng.random())?;
+            if self.game.hand_count() == 0 {
+                break;
+            }
+        }
+        self.hand_count = 0;
+        self.last_states.clear();
+        Ok(())
+    }
+
+    pub(super) fn hand_over(&self) -> bool {
+        self.game.hand_count() > self.hand_count
+    }
+
+    pub(super) fn n_actions(&self) -> usize {
+        self.bets.pot_fractions.len() + 3
+    }
+
+    fn index(&self, action: AbstractAction) -> usize {
+        match action {
+            AbstractAction::Fold => 0,
+            AbstractAction::Call => 1,
+            AbstractAction::PotRaise(fraction) => {
+                // abstract actions only use the fractions of the abstraction
+                2 + self
+                    .bets
+                    .pot_fractions
+                    .iter()
+                    .position(|&f| f == fraction)
+                    .unwrap()
+            }
+            AbstractAction::AllIn => self.n_actions() - 1,
+        }
+    }
+
+    /// One for the actions `seat` can take, all zeros when it is not its turn
+    pub(super) fn action_mask(&self, seat: usize) -> Vec<i8> {
+        let mut mask = vec![0; self.n_actions()];
+        if self.hand_over() || self.game.current_seat() != seat {
+            return mask;
+        }
+        let situation = BetSituation::from(&self.game.get_observable_state(seat));
+        for action in self.bets.abstract_actions(&situation) {
+            mask[self.index(action)] = 1;
+        }
+        mask
+    }
+
+    /// Action of index `index` for `seat`, which the action mask must allow
+    pub(super) fn action(&self, seat: usize, index: usize) -> PyResult<Action> {
+        let mask = self.action_mask(seat);
+        if mask.get(index) != Some(&1) {
+            let legal: Vec<usize> = (0..mask.len()).filter(|&i| mask[i] == 1).collect();
+            return Err(InvalidActionError::new_err(format!(
+                "action {index} is not legal for seat {seat}: {legal:?}"
+            )));
+        }
+        let action = match index {
+            0 => AbstractAction::Fold,
+            1 => AbstractAction::Call,
+            index if index == self.n_actions() - 1 => AbstractAction::AllIn,
+            index => AbstractAction::PotRaise(self.bets.pot_fractions[index - 2]),
+        };
+        let situation = BetSituation::from(&self.game.get_observable_state(seat));
+        Ok(self.bets.to_action(action, &situation))
+    }
+
+    pub(super) fn play(&mut self, action: Action, agents: &mut [Box<dyn Agent>]) -> PyResult<()> {
+        self.last_states = (0..self.settings.n_players)
+            .map(|seat| self.game.get_observable_state(seat))
+            .collect();
+        let state = &self.last_states[self.game.current_seat()];
+        play_action(&mut self.game, agents, state, action)?;
+        Ok(())
+    }
+
+    /// What `seat` sees, the last state before the final action once the hand is over
+    fn state(&self, seat: usize) -> ObservableState {
+        if self.hand_over() {
+            self.last_states[seat].clone()
+        } else {
+            self.game.get_observable_state(seat)
+        }
+    }
+
+    /// Chips won over the hand in big blinds, zero until it is over
+    pub(super) fn reward(&self, seat: usize) -> f64 {
+        match self.game.last_hand_result() {
+            Some(result) if self.hand_over() => {
+                result.deltas[seat] as f64 / self.settings.big_blind.as_f64()
+            }
+            _ => 0.0,
+        }
+    }
+
+    fn observation<'py>(&self, py: Python<'py>, seat: usize) -> PyResult<Bound<'py, PyDict>> {
+        let observation = PyDict::new(py);
+        let features = self.encoder.encode(&self.state(seat))?;
+        observation.set_item("observation", PyArray1::from_vec(py, features))?;
+        observation.set_item(
+            "action_mask",
+            PyArray1::from_vec(py, self.action_mask(seat)),
+        )?;
+        Ok(observation)
+    }
+
+    /// Info dictionary holding the `PyObservation` of `seat`
+    fn info<'py>(&self, py: Python<'py>, seat: usize) -> PyResult<Bound<'py, PyDict>> {
+        let info = PyDict::new(py);
+        info.set_item(
+            "state",
+            PyObservation {
+                state: self.state(seat),
+            },
+        )?;
+        Ok(info)
+    }
+
+    fn action_space<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        py.import("gymnasium.spaces")?
+            .getattr("Discrete")?
+            .call1((self.n_actions(),))
+    }
+
+    /// Dictionary space of the encoded observation and of the action mask, every feature lying
+    /// between zero and one
+    fn observation_space<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let spaces = py.import("gymnasium.spaces")?;
+        let space = |size: usize, dtype: Bound<'py, PyAny>| {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("shape", PyTuple::new(py, [size])?)?;
+            kwargs.set_item("dtype", dtype)?;
+            spaces.getattr("Box")?.call((0, 1), Some(&kwargs))
+        };
+        let observation = PyDict::new(py);
+        observation.set_item(
+            "observation",
+            space(self.encoder.size(), numpy::dtype::<f32>(py).into_any())?,
+        )?;
+        observation.set_item(
+            "action_mask",
+            space(self.n_actions(), numpy::dtype::<i8>(py).into_any())?,
+        )?;
+        spaces.getattr("Dict")?.call1((observation,))
+    }
+}
+
+/// Multi-agent environment following the PettingZoo AEC API, one episode being one hand
+///
+/// Agents are named `player_0` onwards after their seat. Observations are dictionaries of the
+/// NumPy arrays of the encoded observation, see `PyObservation.encode`, and of the action mask,
+/// the `PyObservation` itself being under `state` in the infos. Rewards are the chips won or
+/// lost over the hand in big blinds, given to every agent when it ends, at which point every
+/// agent is terminated.
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct PyPokerEnv {
+    core: EnvCore,
+    agents: Vec<usize>,
+    agent_selection: usize,
+    rewards: Vec<f64>,
+    cumulative_rewards: Vec<f64>,
+    terminations: Vec<bool>,
+}
+
+impl PyPokerEnv {
+    fn name(seat: usize) -> String {
+        format!("player_{seat}")
+    }
+
+    fn seat(&self, agent: &str) -> PyResult<usize> {
+        agent
+            .strip_prefix("player_")
+            .and_then(|seat| seat.parse().ok())
+            .filter(|&seat| seat < self.core.settings.n_players)
+            .ok_or_else(|| PyKeyError::new_err(agent.to_string()))
+    }
+
+    /// Values of the agents still in the episode, by name
+    fn agent_dict<'py, T: IntoPyObject<'py> + Copy>(
+        &self,
+        py: Python<'py>,
+        values: &[T],
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for &seat in &self.agents {
+            dict.set_item(Self::name(seat), values[seat])?;
+        }
+        Ok(dict)
+    }
+
+    /// Agent after `seat` in seat order among the agents left
+    fn next_agent(&self, seat: usize) -> usize {
+        self.agents
+            .iter()
+            .copied()
+            .find(|&agent| agent > seat)
+            .or(self.agents.first().copied())
+            .unwrap_or(seat)
+    }
+
+    /// Removes the terminated agent selected, as PettingZoo expects once an episode is over
+    fn dead_step(&mut self) {
+        let seat = self.agent_selection;
+        self.agents.retain(|&agent| agent != seat);
+        self.agent_selection = self.next_agent(seat);
+        self.rewards.fill(0.0);
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyPokerEnv {
+    #[new]
+    #[pyo3(signature = (settings, pot_fractions=vec![0.5, 1.0, 2.0], max_history=ObservationEncoder::DEFAULT_MAX_HISTORY))]
+    pub fn new(
+        settings: &PySettings,
+        pot_fractions: Vec<f64>,
+        max_history: usize,
+    ) -> PyResult<Self> {
+        let core = EnvCore::new(settings, pot_fractions, max_history)?;
+        let n = core.settings.n_players;
+        let mut env = PyPokerEnv {
+            core,
+            agents: Vec::new(),
+            agent_selection: 0,
+            rewards: vec![0.0; n],
+            cumulative_rewards: vec![0.0; n],
+            terminations: vec![false; n],
+        };
+        env.reset(None, None)?;
+        Ok(env)
+    }
+
+    #[getter]
+    pub fn metadata<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let metadata = PyDict::new(py);
+        metadata.set_item("name", "pokr_v0")?;
+        metadata.set_item("render_modes", Vec::<String>::new())?;
+        metadata.set_item("is_parallelizable", false)?;
+        Ok(metadata)
+    }
+
+    #[getter]
+    pub fn possible_agents(&self) -> PyResult<Vec<String>> {
+        Ok((0..self.core.settings.n_players).map(Self::name).collect())
+    }
+
+    /// Agents still in the episode, terminated agents leaving once stepped with `None`
+    #[getter]
+    pub fn agents(&self) -> PyResult<Vec<String>> {
+        Ok(self.agents.iter().copied().map(Self::name).collect())
+    }
+
+    #[getter]
+    pub fn num_agents(&self) -> PyResult<usize> {
+        Ok(self.agents.len())
+    }
+
+    #[getter]
+    pub fn max_num_agents(&self) -> PyResult<usize> {
+        Ok(self.core.settings.n_players)
+    }
+
+    /// Agent to step next
+    #[getter]
+    pub fn agent_selection(&self) -> PyResult<String> {
+        Ok(Self::name(self.agent_selection))
+    }
+
+    /// Rewards of the last step
+    #[getter]
+    pub fn rewards<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        self.agent_dict(py, &self.rewards)
+    }
+
+    /// Rewards since each agent last acted, as returned by `last`
+    #[getter]
+    pub fn _cumulative_rewards<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        self.agent_dict(py, &self.cumulative_rewards)
+    }
+
+    #[getter]
+    pub fn terminations<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        self.agent_dict(py, &self.terminations)
+    }
+
+    /// Always false, a hand never stops before its end
+    #[getter]
+    pub fn truncations<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        self.agent_dict(py, &vec![false; self.core.settings.n_players])
+    }
+
+    #[getter]
+    pub fn infos<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let infos = PyDict::new(py);
+        for &seat in &self.agents {
+            infos.set_item(Self::name(seat), self.core.info(py, seat)?)?;
+        }
+        Ok(infos)
+    }
+
+    /// Deals a new hand, the seed making the hands that follow reproducible
+    #[pyo3(signature = (seed=None, options=None))]
+    pub fn reset(&mut self, seed: Option<u64>, options: Option<Bound<'_, PyDict>>) -> PyResult<()> {
+        let _ = options;
+        self.core.reset(seed)?;
+        self.agents = (0..self.core.settings.n_players).collect();
+        self.agent_selection = self.core.game.current_seat();
+        self.rewards.fill(0.0);
+        self.cumulative_rewards.fill(0.0);
+        self.terminations.fill(false);
+        Ok(())
+    }
+
+    /// Plays the action of index `action` for the selected agent, `None` once it is terminated
+    ///
+    /// Raises `InvalidActionError` when the action mask does not allow the action.
+    #[pyo3(signature = (action))]
+    pub fn step(&mut self, action: Option<usize>) -> PyResult<()> {
+        let seat = self.agent_selection;
+        if self.terminations[seat] {
+            if action.is_some() {
+                return Err(PyValueError::new_err(
+                    "a terminated agent can only step with None",
+                ));
+            }
+            self.dead_step();
+            return Ok(());
+        }
+        let Some(action) = action else {
+            return Err(PyValueError::new_err(
+                "only terminated agents step with None",
+            ));
+        };
+
+        let action = self.core.action(seat, action)?;
+        self.core.play(action, &mut [])?;
+        self.cumulative_rewards[seat] = 0.0;
+        if self.core.hand_over() {
+            for &agent in &self.agents {
+                self.rewards[agent] = self.core.reward(agent);
+                self.terminations[agent] = true;
+            }
+            self.agent_selection = self.next_agent(seat);
+        } else {
+            self.rewards.fill(0.0);
+            self.agent_selection = self.core.game.current_seat();
+        }
+        for &agent in &self.agents {
+            self.cumulative_rewards[agent] += self.rewards[agent];
+        }
+        Ok(())
+    }
+
+    /// Observation of `agent`, as a dictionary of its `PyObservation` and its action mask
+    pub fn observe<'py>(&self, py: Python<'py>, agent: &str) -> PyResult<Bound<'py, PyDict>> {
+        self.core.observation(py, self.seat(agent)?)
+    }
+
+    /// One for the actions `agent` can take, all zeros when it is not its turn
+    pub fn action_mask<'py>(
+        &self,
+        py: Python<'py>,
+        agent: &str,
+    ) -> PyResult<Bound<'py, PyArray1<i8>>> {
+        Ok(PyArray1::from_vec(
+            py,
+            self.core.action_mask(self.seat(agent)?),
+        ))
+    }
+
+    /// Observation, cumulative reward, termination, truncation and info of the selected agent
+    #[pyo3(signature = (observe=true))]
+    pub fn last<'py>(&self, py: Python<'py>, observe: bool) -> PyResult<Last<'py>> {
+        let seat = self.agent_selection;
+        let observation = match observe {
+            true => Some(self.core.observation(py, seat)?),
+            false => None,
+        };
+        Ok((
+            observation,
+            self.cumulative_rewards[seat],
+            self.terminations[seat],
+            false,
+            self.core.info(py, seat)?,
+        ))
+    }
+
+    /// Agents to step in turn until every agent left the episode
+    #[pyo3(signature = (max_iter=usize::MAX))]
+    pub fn agent_iter(slf: Py<Self>, max_iter: usize) -> PyResult<PyAgentIter> {
+        Ok(PyAgentIter {
+            env: slf,
+            left: max_iter,
+        })
+    }
+
+    /// `gymnasium.spaces.Discrete` over the action indices, gymnasium being imported lazily
+    pub fn action_space<'py>(&self, py: Python<'py>, agent: &str) -> PyResult<Bound<'py, PyAny>> {
+        self.seat(agent)?;
+        self.core.action_space(py)
+    }
+
+    /// `gymnasium.spaces.Dict` of the observations, see `observation_space` of `PyPokerGymEnv`
+    pub fn observation_space<'py>(
+        &self,
+        py: Python<'py>,
+        agent: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.seat(agent)?;
+        self.core.observation_space(py)
+    }
+
+    pub fn close(&self) -> PyResult<()> {
+        Ok(())
+    }
+}
+
+/// Iterator over the agents of a `PyPokerEnv`, see `PyPokerEnv.agent_iter`
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct PyAgentIter {
+    env: Py<PyPokerEnv>,
+    left: usize,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyAgentIter {
+    pub fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    pub fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<String>> {
+        let env = self.env.borrow(py);
+        if self.left == 0 || env.agents.is_empty() {
+            return Ok(None);
+        }
+        self.left -= 1;
+        Ok(Some(PyPokerEnv::name(env.agent_selection)))
+    }
+}
+
+/// Single-agent environment following the Gymnasium API, the other seats played by fixed bots
+///
+/// The learner sits in seat 0 and the button moves at random between episodes, one episode
+/// being one hand. Observations and rewards are the ones of `PyPokerEnv`. Opponents are named
+/// after their bots: `random`, `calling`, `raising`, `tight_aggressive` and `pot_odds`.
+#[gen_stub_pyclass]
+#[pyclass(unsendable)]
+pub struct PyPokerGymEnv {
+    core: EnvCore,
+    opponents: Vec<String>,
+    /// One agent per seat, the one of the learner never being asked for an action
+    agents: Vec<Box<dyn Agent>>,
+}
+
+impl PyPokerGymEnv {
+    const SEAT: usize = 0;
+
+    fn bot(name: &str, seed: u64) -> PyResult<Box<dyn Agent>> {
+        Ok(match name {
+            "random" => Box::new(RandomAgent::new(seed)),
+            "calling" => Box::new(CallingAgent),
+            "raising" => Box::new(RaisingAgent),
+            "tight_aggressive" => Box::new(TightAggressiveAgent::new(seed)),
+            "pot_odds" => Box::new(PotOddsAgent::new(seed)),
+            _ => return Err(PyValueError::new_err(format!("no bot named {name:?}"))),
+        })
+    }
+
+    /// Lets the bots act until it is the turn of the learner or the hand is over
+    fn play_opponents(&mut self) -> PyResult<()> {
+        while !self.core.hand_over() && self.core.game.current_seat() != Self::SEAT {
+            let state = self
+                .core
+                .game
+                .get_observable_state(self.core.game.current_seat());
+            let action = self.agents[state.seat].choose_action(&state, &state.legal_actions());
+            self.core.play(action, &mut self.agents)?;
+        }
+        if self.core.hand_over() {
+            end_hand(&self.core.game, &mut self.agents);
+        }
+        Ok(())
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyPokerGymEnv {
+    /// Game of `settings` with one opponent per seat but the first
+    #[new]
+    #[pyo3(signature = (settings, opponents, pot_fractions=vec![0.5, 1.0, 2.0], max_history=ObservationEncoder::DEFAULT_MAX_HISTORY))]
+    pub fn new(
+        settings: &PySettings,
+        opponents: Vec<String>,
+        pot_fractions: Vec<f64>,
+        max_history: usize,
+    ) -> PyResult<Self> {
+        let core = EnvCore::new(settings, pot_fractions, max_history)?;
+        if opponents.len() + 1 != core.settings.n_players {
+            return Err(PyValueError::new_err(format!(
+                "{} opponents cannot fill a table of {}",
+                opponents.len(),
+                core.settings.n_players
+            )));
+        }
+        for name in &opponents {
+            Self::bot(name, 0)?;
+        }
+        Ok(PyPokerGymEnv {
+            core,
+            opponents,
+            agents: Vec::new(),
+        })
+    }
+
+    /// Deals a new hand and lets the bots act until the learner has a decision to make
+    ///
+    /// Returns the observation and an info dictionary.
+    #[pyo3(signature = (seed=None, options=None))]
+    pub fn reset<'py>(
+        &mut self,
+        py: Python<'py>,
+        seed: Option<u64>,
+        options: Option<Bound<'py, PyDict>>,
+    ) -> PyResult<(Bound<'py, PyDict>, Bound<'py, PyDict>)> {
+        let _ = options;
+        let mut seed = seed;
+        loop {
+            self.core.reset(seed.take())?;
+            let mut agents = vec![Box::new(CallingAgent) as Box<dyn Agent>];
+            for name in &self.opponents {
+                agents.push(Self::bot(name, self.core.rng.random())?);
+            }
+            self.agents = agents;
+            start_hand(&self.core.game, &mut self.agents)?;
+            self.play_opponents()?;
+            // the blinds can leave the learner all-in without a decision to make
+            if !self.core.hand_over() {
+                break;
+            }
+        }
+        Ok((
+            self.core.observation(py, Self::SEAT)?,
+            self.core.info(py, Self::SEAT)?,
+        ))
+    }
+
+    /// Plays the action of index `action` for the learner, then the bots until its next turn
+    ///
+    /// Returns the observation, the reward, whether the hand is over, whether it was truncated
+    /// and an info dictionary. Raises `InvalidActionError` when the action mask does not allow
+    /// the action, and `ValueError` before the first `reset`.
+    pub fn step<'py>(&mut self, py: Python<'py>, action: usize) -> PyResult<Step<'py>> {
+        if self.agents.is_empty() {
+            return Err(PyValueError::new_err("call reset first"));
+        }
+        if self.core.hand_over() {
+            return Err(PyValueError::new_err("the hand is over, call reset"));
+        }
+        let action = self.core.action(Self::SEAT, action)?;
+        self.core.play(action, &mut self.agents)?;
+        self.play_opponents()?;
+        Ok((
+            self.core.observation(py, Self::SEAT)?,
+            self.core.reward(Self::SEAT),
+            self.core.hand_over(),
+            false,
+            self.core.info(py, Self::SEAT)?,
+        ))
+    }
+
+    /// One for the actions the learner can take
+    pub fn action_mask<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray1<i8>>> {
+        Ok(PyArray1::from_vec(py, self.core.action_mask(Self::SEAT)))
+    }
+
+    /// `gymnasium.spaces.Discrete` over the action indices, gymnasium being imported lazily
+    #[getter]
+    pub fn action_space<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.core.action_space(py)
+    }
+
+    /// `gymnasium.spaces.Dict` of the encoded observation, see `PyObservation.encode`, and of
+    /// the action mask
+    #[getter]
+    pub fn observation_space<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.core.observation_space(py)
+    }
+
+    pub fn close(&self) -> PyResult<()> {
+        Ok(())
+    }
+}
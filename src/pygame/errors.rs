@@ -0,0 +1,159 @@
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3_stub_gen::type_info::PyClassInfo;
+use pyo3_stub_gen::{PyStubType, TypeInfo};
+
+/// `pyo3_stub_gen::create_exception`, except that the stub refers to a base defined in this
+/// module by its name instead of a builtin
+macro_rules! exception {
+    ($name: ident, $base: ty, $doc: expr) => {
+        pyo3::create_exception!(pokr, $name, $base, $doc);
+
+        impl PyStubType for $name {
+            fn type_output() -> TypeInfo {
+                TypeInfo::locally_defined(stringify!($name), "pokr".into())
+            }
+        }
+
+        pyo3_stub_gen::inventory::submit! {
+            PyClassInfo {
+                pyclass_name: stringify!($name),
+                struct_id: std::any::TypeId::of::<$name>,
+                getters: &[],
+                setters: &[],
+                module: Some("pokr"),
+                doc: $doc,
+                bases: &[|| <$base as PyStubType>::type_output()],
+                has_eq: false,
+                has_ord: false,
+                has_hash: false,
+                has_str: false,
+                subclass: true,
+            }
+        }
+    };
+}
+
+exception!(
+    PokrError,
+    PyException,
+    "Base class of the errors raised by pokr"
+);
+exception!(GameError, PokrError, "Error of the game engine");
+exception!(
+    InvalidActionError,
+    GameError,
+    "Action is not legal for the seat to act"
+);
+exception!(
+    InvalidRoundCardCountError,
+    GameError,
+    "Board holds a card count no round has"
+);
+exception!(
+    GameOverError,
+    GameError,
+    "Fewer than two seats have chips left"
+);
+exception!(InvalidSetupError, GameError, "Hand setup cannot be dealt");
+exception!(
+    InvalidSettingsError,
+    GameError,
+    "Settings cannot be played with"
+);
+exception!(
+    InvalidBytesError,
+    GameError,
+    "Bytes that do not describe a game"
+);
+exception!(
+    InvalidSeatChangeError,
+    GameError,
+    "Player cannot join, leave or buy chips at the seat"
+);
+exception!(
+    AgentCountMismatchError,
+    GameError,
+    "Agents given do not match the seats one to one"
+);
+exception!(CardError, PokrError, "Error of the cards, decks and boards");
+exception!(BoardOverflowError, CardError, "Card added to a full board");
+exception!(
+    InvalidPokerHandCardCountError,
+    CardError,
+    "Poker hand without five cards"
+);
+exception!(EmptyDeckError, CardError, "Card drawn from an empty deck");
+exception!(
+    InvalidCardError,
+    CardError,
+    "Text that does not describe cards"
+);
+
+impl From<crate::GameError> for PyErr {
+    fn from(error: crate::GameError) -> Self {
+        let message = error.to_string();
+        match error {
+            crate::GameError::InvalidAction => InvalidActionError::new_err(message),
+            crate::GameError::InvalidRoundCardCount => InvalidRoundCardCountError::new_err(message),
+            crate::GameError::GameOver => GameOverError::new_err(message),
+            crate::GameError::InvalidSetup => InvalidSetupError::new_err(message),
+            crate::GameError::InvalidSettings => InvalidSettingsError::new_err(message),
+            crate::GameError::InvalidBytes => InvalidBytesError::new_err(message),
+            crate::GameError::InvalidSeatChange => InvalidSeatChangeError::new_err(message),
+            crate::GameError::AgentCountMismatch { .. } => {
+                AgentCountMismatchError::new_err(message)
+            }
+            crate::GameError::Card(error) => error.into(),
+        }
+    }
+}
+
+impl From<crate::CardError> for PyErr {
+    fn from(error: crate::CardError) -> Self {
+        let message = error.to_string();
+        match error {
+            crate::CardError::BoardOverflow => BoardOverflowError::new_err(message),
+            crate::CardError::InvalidPokerHandCardCount => {
+                InvalidPokerHandCardCountError::new_err(message)
+            }
+            crate::CardError::EmptyDeck => EmptyDeckError::new_err(message),
+            crate::CardError::InvalidCard(_) => InvalidCardError::new_err(message),
+        }
+    }
+}
+
+pub(crate) fn add_exceptions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("PokrError", py.get_type::<PokrError>())?;
+    m.add("GameError", py.get_type::<GameError>())?;
+    m.add("InvalidActionError", py.get_type::<InvalidActionError>())?;
+    m.add(
+        "InvalidRoundCardCountError",
+        py.get_type::<InvalidRoundCardCountError>(),
+    )?;
+    m.add("GameOverError", py.get_type::<GameOverError>())?;
+    m.add("InvalidSetupError", py.get_type::<InvalidSetupError>())?;
+    m.add(
+        "InvalidSettingsError",
+        py.get_type::<InvalidSettingsError>(),
+    )?;
+    m.add("InvalidBytesError", py.get_type::<InvalidBytesError>())?;
+    m.add(
+        "InvalidSeatChangeError",
+        py.get_type::<InvalidSeatChangeError>(),
+    )?;
+    m.add(
+        "AgentCountMismatchError",
+        py.get_type::<AgentCountMismatchError>(),
+    )?;
+    m.add("CardError", py.get_type::<CardError>())?;
+    m.add("BoardOverflowError", py.get_type::<BoardOverflowError>())?;
+    m.add(
+        "InvalidPokerHandCardCountError",
+        py.get_type::<InvalidPokerHandCardCountError>(),
+    )?;
+    m.add("EmptyDeckError", py.get_type::<EmptyDeckError>())?;
+    m.add("InvalidCardError", py.get_type::<InvalidCardError>())?;
+    Ok(())
+}
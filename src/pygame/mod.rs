@@ -0,0 +1,326 @@
+mod cards;
+mod env;
+mod errors;
+mod observation;
+mod vec_game;
+
+pub use cards::*;
+pub use env::*;
+pub(crate) use errors::add_exceptions;
+pub use observation::*;
+pub use vec_game::*;
+
+use crate::*;
+
+use pyo3::exceptions::PyIndexError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use pyo3_stub_gen::derive::*;
+
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct PySettings {
+    settings: Settings,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PySettings {
+    #[new]
+    /// No-limit and no rake unless others are given, amounts shown as plain chips unless a
+    /// currency `symbol` and the `decimals` of a chip are given, `"$"` and 2 for cents
+    #[pyo3(signature = (n_players, initial_stack, small_blind=5, big_blind=10, betting=None, rake=None, symbol=String::new(), decimals=0))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        n_players: usize,
+        initial_stack: usize,
+        small_blind: usize,
+        big_blind: usize,
+        betting: Option<PyBettingStructure>,
+        rake: Option<PyRake>,
+        symbol: String,
+        decimals: u32,
+    ) -> PyResult<Self> {
+        let settings = Settings {
+            n_players,
+            initial_stack: initial_stack.into(),
+            small_blind: small_blind.into(),
+            big_blind: big_blind.into(),
+            betting: betting.map(|betting| betting.betting).unwrap_or_default(),
+            rake: rake.map(|rake| rake.rake).unwrap_or_default(),
+            denomination: Denomination { symbol, decimals },
+        };
+        settings.validate()?;
+        Ok(PySettings { settings })
+    }
+}
+
+#[gen_stub_pyclass]
+#[pyclass(eq, hash, frozen)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PyBettingStructure {
+    betting: BettingStructure,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyBettingStructure {
+    #[staticmethod]
+    pub fn new_no_limit() -> PyResult<Self> {
+        Ok(PyBettingStructure {
+            betting: BettingStructure::NoLimit,
+        })
+    }
+
+    /// Raises up to the pot after calling
+    #[staticmethod]
+    pub fn new_pot_limit() -> PyResult<Self> {
+        Ok(PyBettingStructure {
+            betting: BettingStructure::PotLimit,
+        })
+    }
+
+    /// Small bet on the preflop and the flop, big bet on the turn and the river, and at most
+    /// `max_bets` bets and raises per street, the big blind counting as the first one preflop
+    #[staticmethod]
+    #[pyo3(signature = (small_bet, big_bet, max_bets=4))]
+    pub fn new_fixed_limit(small_bet: usize, big_bet: usize, max_bets: usize) -> PyResult<Self> {
+        Ok(PyBettingStructure {
+            betting: BettingStructure::FixedLimit {
+                small_bet: small_bet.into(),
+                big_bet: big_bet.into(),
+                max_bets,
+            },
+        })
+    }
+
+    pub fn __repr__(&self) -> PyResult<String> {
+        Ok(match self.betting {
+            BettingStructure::NoLimit => "PyBettingStructure.new_no_limit()".to_string(),
+            BettingStructure::PotLimit => "PyBettingStructure.new_pot_limit()".to_string(),
+            BettingStructure::FixedLimit {
+                small_bet,
+                big_bet,
+                max_bets,
+            } => format!("PyBettingStructure.new_fixed_limit({small_bet}, {big_bet}, {max_bets})"),
+        })
+    }
+}
+
+#[gen_stub_pyclass]
+#[pyclass(eq, hash, frozen)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PyRake {
+    rake: Rake,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyRake {
+    /// `basis_points` hundredths of a percent of the called pot, capped by the `(min_players, cap)`
+    /// pair of `caps` with the largest `min_players` not exceeding the players dealt in
+    #[new]
+    #[pyo3(signature = (basis_points, caps=Vec::new(), no_flop_no_drop=false))]
+    pub fn new(
+        basis_points: usize,
+        caps: Vec<(usize, usize)>,
+        no_flop_no_drop: bool,
+    ) -> PyResult<Self> {
+        Ok(PyRake {
+            rake: Rake {
+                basis_points,
+                caps: caps
+                    .into_iter()
+                    .map(|(seats, cap)| (seats, cap.into()))
+                    .collect(),
+                no_flop_no_drop,
+            },
+        })
+    }
+
+    pub fn __repr__(&self) -> PyResult<String> {
+        Ok(format!(
+            "PyRake({}, {:?}, {})",
+            self.rake.basis_points,
+            self.rake
+                .caps
+                .iter()
+                .map(|&(seats, cap)| (seats, cap.get()))
+                .collect::<Vec<_>>(),
+            if self.rake.no_flop_no_drop {
+                "True"
+            } else {
+                "False"
+            }
+        ))
+    }
+}
+
+#[gen_stub_pyclass]
+#[pyclass(eq, hash, frozen)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PyAction {
+    pub(crate) action: Action,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyAction {
+    #[staticmethod]
+    pub fn new_fold() -> PyResult<Self> {
+        Ok(PyAction {
+            action: Action::Fold,
+        })
+    }
+
+    #[staticmethod]
+    pub fn new_raise(amount: usize) -> PyResult<Self> {
+        Ok(PyAction {
+            action: Action::Raise(Chips::new(amount)),
+        })
+    }
+
+    #[staticmethod]
+    pub fn new_check() -> PyResult<Self> {
+        Ok(PyAction {
+            action: Action::Check,
+        })
+    }
+
+    #[staticmethod]
+    pub fn new_call() -> PyResult<Self> {
+        Ok(PyAction {
+            action: Action::Call,
+        })
+    }
+
+    pub fn __repr__(&self) -> PyResult<String> {
+        Ok(match self.action {
+            Action::Fold => "PyAction.new_fold()".to_string(),
+            Action::Raise(amount) => format!("PyAction.new_raise({amount})"),
+            Action::Call => "PyAction.new_call()".to_string(),
+            Action::Check => "PyAction.new_check()".to_string(),
+        })
+    }
+}
+
+#[gen_stub_pyclass]
+// pickling finds the class by its module
+#[pyclass(module = "pokr")]
+pub struct PyGame {
+    game: Game,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyGame {
+    #[new]
+    pub fn new(settings: &PySettings) -> PyResult<Self> {
+        Ok(PyGame {
+            game: Game::new(settings.settings.to_owned())?,
+        })
+    }
+
+    /// Raises `InvalidActionError` when the action is not legal, `GameOverError` once the game
+    /// is over
+    pub fn play_turn(&mut self, action: &PyAction) -> PyResult<()> {
+        let legal_actions = self.game.legal_actions();
+        self.game
+            .play_turn(action.action)
+            .map_err(|error| match error {
+                GameError::InvalidAction => errors::InvalidActionError::new_err(format!(
+                    "{:?} is not legal for seat {}: {}",
+                    action.action,
+                    self.game.current_seat(),
+                    describe(&legal_actions)
+                )),
+                error => error.into(),
+            })
+    }
+
+    pub fn current_seat(&self) -> PyResult<usize> {
+        Ok(self.game.current_seat())
+    }
+
+    /// What `seat` can see of the game
+    pub fn observation(&self, seat: usize) -> PyResult<PyObservation> {
+        if seat >= self.game.settings().n_players {
+            return Err(PyIndexError::new_err(format!("no seat {seat}")));
+        }
+        Ok(PyObservation {
+            state: self.game.get_observable_state(seat),
+        })
+    }
+
+    /// Number of hands played to the end
+    pub fn hand_count(&self) -> PyResult<usize> {
+        Ok(self.game.hand_count())
+    }
+
+    pub fn over(&self) -> PyResult<bool> {
+        Ok(self.game.over())
+    }
+
+    /// Amount of chips written in the denomination of the game, such as `$0.02`
+    pub fn format_chips(&self, amount: usize) -> PyResult<String> {
+        Ok(self.game.settings().denomination.format(amount))
+    }
+
+    /// Change of the prize equity of every seat over the last hand, `payouts` holding the prize
+    /// of every place from the first, or `None` before the first hand ends
+    pub fn icm_deltas(&self, payouts: Vec<f64>) -> PyResult<Option<Vec<f64>>> {
+        Ok(self.game.icm_deltas(&payouts))
+    }
+
+    /// Whole game as bytes, its generator included, see `from_bytes`
+    #[cfg(feature = "serde")]
+    pub fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new(py, &self.game.to_bytes()))
+    }
+
+    /// Game written by `to_bytes`, raises `InvalidBytesError` for anything else
+    #[cfg(feature = "serde")]
+    #[staticmethod]
+    pub fn from_bytes(bytes: &Bound<'_, PyBytes>) -> PyResult<Self> {
+        Ok(PyGame {
+            game: Game::from_bytes(bytes.as_bytes())?,
+        })
+    }
+
+    /// Pickles the game as its bytes, so that an unpickled game deals the same cards
+    #[cfg(feature = "serde")]
+    pub fn __reduce__<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        let from_bytes = py.get_type::<PyGame>().getattr("from_bytes")?;
+        Ok((from_bytes, (self.to_bytes(py)?,)))
+    }
+
+    pub fn __copy__(&self) -> PyResult<Self> {
+        Ok(PyGame {
+            game: self.game.clone(),
+        })
+    }
+
+    /// Same as `__copy__`, a game holding no Python object
+    pub fn __deepcopy__(&self, _memo: &Bound<'_, PyAny>) -> PyResult<Self> {
+        self.__copy__()
+    }
+}
+
+/// Legal actions in words, for error messages
+fn describe(legal_actions: &LegalActions) -> String {
+    let mut actions = vec!["fold".to_string()];
+    if legal_actions.can_check() {
+        actions.push("check".to_string());
+    } else {
+        actions.push(format!("call {}", legal_actions.call_amount));
+    }
+    if let Some((min, max)) = legal_actions.raise_range {
+        actions.push(format!("raise from {min} to {max}"));
+    }
+    actions.join(", ")
+}
+
+define_stub_info_gatherer!(stub_info);
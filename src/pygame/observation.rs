@@ -0,0 +1,206 @@
+use crate::*;
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::*;
+
+#[gen_stub_pyclass_enum]
+#[pyclass(eq, eq_int, ord, hash, frozen)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PyRound {
+    PreFlop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl From<Round> for PyRound {
+    fn from(round: Round) -> Self {
+        match round {
+            Round::PreFlop => PyRound::PreFlop,
+            Round::Flop => PyRound::Flop,
+            Round::Turn => PyRound::Turn,
+            Round::River => PyRound::River,
+        }
+    }
+}
+
+/// What one seat can see of the game
+#[gen_stub_pyclass]
+#[pyclass(eq, hash, frozen)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PyObservation {
+    pub(crate) state: ObservableState,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyObservation {
+    /// Seat the game is observed from
+    #[getter]
+    pub fn seat(&self) -> PyResult<usize> {
+        Ok(self.state.seat)
+    }
+
+    #[getter]
+    pub fn hand(&self) -> PyResult<Option<PyPlayerHand>> {
+        Ok(self.state.hand.map(|hand| PyPlayerHand { hand }))
+    }
+
+    #[getter]
+    pub fn board(&self) -> PyResult<Vec<PyCard>> {
+        Ok(self
+            .state
+            .board
+            .iter()
+            .map(|&card| PyCard { card })
+            .collect())
+    }
+
+    #[getter]
+    pub fn current_seat(&self) -> PyResult<usize> {
+        Ok(self.state.current_seat)
+    }
+
+    #[getter]
+    pub fn sb_seat(&self) -> PyResult<usize> {
+        Ok(self.state.sb_seat)
+    }
+
+    #[getter]
+    pub fn round(&self) -> PyResult<PyRound> {
+        Ok(self.state.round.into())
+    }
+
+    #[getter]
+    pub fn stacks(&self) -> PyResult<Vec<usize>> {
+        Ok(self
+            .state
+            .seats
+            .iter()
+            .map(|seat| seat.stack.get())
+            .collect())
+    }
+
+    /// Bets of the current round
+    #[getter]
+    pub fn bets(&self) -> PyResult<Vec<usize>> {
+        Ok(self.state.seats.iter().map(|seat| seat.bet.get()).collect())
+    }
+
+    #[getter]
+    pub fn folded(&self) -> PyResult<Vec<bool>> {
+        Ok(self.state.seats.iter().map(|seat| seat.is_folded).collect())
+    }
+
+    /// Seats out of chips before the hand started
+    #[getter]
+    pub fn dead(&self) -> PyResult<Vec<bool>> {
+        Ok(self.state.seats.iter().map(|seat| seat.is_dead).collect())
+    }
+
+    /// Stacks at the start of the hand, before the blinds
+    #[getter]
+    pub fn starting_stacks(&self) -> PyResult<Vec<usize>> {
+        Ok(self
+            .state
+            .starting_stacks
+            .iter()
+            .map(|stack| stack.get())
+            .collect())
+    }
+
+    /// Chips collected from the previous rounds
+    #[getter]
+    pub fn pot(&self) -> PyResult<usize> {
+        Ok(self.state.pot.get())
+    }
+
+    /// Chips in the middle, including the bets of the current round
+    #[getter]
+    pub fn total_pot(&self) -> PyResult<usize> {
+        Ok(self.state.total_pot().get())
+    }
+
+    #[getter]
+    pub fn small_blind(&self) -> PyResult<usize> {
+        Ok(self.state.small_blind.get())
+    }
+
+    #[getter]
+    pub fn big_blind(&self) -> PyResult<usize> {
+        Ok(self.state.big_blind.get())
+    }
+
+    /// Chips the seat to act needs to call, zero when it can check
+    #[getter]
+    pub fn call_amount(&self) -> PyResult<usize> {
+        Ok(self.state.legal_actions().call_amount.get())
+    }
+
+    /// Smallest and largest amount a raise can add on top of the call
+    #[getter]
+    pub fn raise_range(&self) -> PyResult<Option<(usize, usize)>> {
+        Ok(self
+            .state
+            .legal_actions()
+            .raise_range
+            .map(|(min, max)| (min.get(), max.get())))
+    }
+
+    /// Actions of the hand as seat, round and action
+    #[getter]
+    pub fn history(&self) -> PyResult<Vec<(usize, PyRound, PyAction)>> {
+        Ok(self
+            .state
+            .history
+            .iter()
+            .map(|record| {
+                (
+                    record.seat,
+                    record.round.into(),
+                    PyAction {
+                        action: record.action,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// Observation as a float32 NumPy array for neural networks, keeping the last
+    /// `max_history` actions
+    ///
+    /// Holds the hole cards and the board one-hot, the stacks, bets and pot over the chips in
+    /// play from this seat onwards, the position and the street one-hot, then one row per
+    /// action of the seat, street and kind one-hot and of the raise amount.
+    #[pyo3(signature = (max_history=ObservationEncoder::DEFAULT_MAX_HISTORY))]
+    pub fn encode<'py>(
+        &self,
+        py: Python<'py>,
+        max_history: usize,
+    ) -> PyResult<Bound<'py, PyArray1<f32>>> {
+        let encoder = ObservationEncoder {
+            n_players: self.state.seats.len(),
+            max_history,
+        };
+        Ok(PyArray1::from_vec(py, encoder.encode(&self.state)?))
+    }
+
+    pub fn __repr__(&self) -> PyResult<String> {
+        let state = &self.state;
+        let hand = state
+            .hand
+            .map(|hand| hand.cards().map(Card::to_string).collect::<String>());
+        let board: String = state.board.iter().map(Card::to_string).collect();
+        Ok(format!(
+            "PyObservation(seat={}, hand={}, board='{board}', round={:?}, stacks={:?}, bets={:?}, pot={}, current_seat={})",
+            state.seat,
+            hand.map_or("None".to_string(), |hand| format!("'{hand}'")),
+            state.round,
+            self.stacks()?,
+            self.bets()?,
+            state.pot,
+            state.current_seat,
+        ))
+    }
+}
@@ -0,0 +1,219 @@
+use crate::*;
+
+use super::env::EnvCore;
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray, PyArray1, PyArray2, PyReadonlyArray1};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::*;
+use rayon::prelude::*;
+
+/// Observations, action masks and seats to act
+type Reset<'py> = (
+    Bound<'py, PyArray2<f32>>,
+    Bound<'py, PyArray2<i8>>,
+    Bound<'py, PyArray1<i64>>,
+);
+/// Observations, action masks, rewards, done flags and seats to act
+type Step<'py> = (
+    Bound<'py, PyArray2<f32>>,
+    Bound<'py, PyArray2<i8>>,
+    Bound<'py, PyArray2<f32>>,
+    // a boolean array, which stubs cannot describe
+    Bound<'py, PyAny>,
+    Bound<'py, PyArray1<i64>>,
+);
+
+/// One row per game, filled without holding the GIL
+struct Batch {
+    observations: Vec<f32>,
+    action_masks: Vec<i8>,
+    rewards: Vec<f32>,
+    dones: Vec<bool>,
+    seats: Vec<i64>,
+}
+
+/// Many independent games stepped together, to collect training data
+///
+/// Every game plays hands like `PyPokerEnv`, the seat to act in each game taking the action
+/// given for that game, and finished hands are replaced by new ones right away. Games are
+/// stepped on several threads with the GIL released, and results come back as NumPy arrays
+/// with one row per game. Observations are the ones of the seat to act.
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct PyVecGame {
+    cores: Vec<EnvCore>,
+}
+
+impl PyVecGame {
+    fn batch(&self) -> Batch {
+        let n = self.cores.len();
+        let core = &self.cores[0];
+        Batch {
+            observations: vec![0.0; n * core.encoder.size()],
+            action_masks: vec![0; n * core.n_actions()],
+            rewards: vec![0.0; n * core.settings.n_players],
+            dones: vec![false; n],
+            seats: vec![0; n],
+        }
+    }
+
+    /// Writes the observation, the action mask and the seat to act of every game
+    fn observe(&self, batch: &mut Batch) -> Result<(), GameError> {
+        let core = &self.cores[0];
+        let (size, n_actions) = (core.encoder.size(), core.n_actions());
+        self.cores
+            .par_iter()
+            .zip(batch.observations.par_chunks_mut(size))
+            .zip(batch.action_masks.par_chunks_mut(n_actions))
+            .zip(batch.seats.par_iter_mut())
+            .try_for_each(|(((core, observation), action_mask), seat)| {
+                let current_seat = core.game.current_seat();
+                let state = core.game.get_observable_state(current_seat);
+                core.encoder.encode_into(&state, observation)?;
+                action_mask.copy_from_slice(&core.action_mask(current_seat));
+                *seat = current_seat as i64;
+                Ok(())
+            })
+    }
+
+    /// Deals a new hand in every game, reseeding them first when a seed is given
+    fn reseed(&mut self, seed: Option<u64>) -> PyResult<()> {
+        self.cores
+            .iter_mut()
+            .enumerate()
+            .try_for_each(|(i, core)| core.reset(seed.map(|seed| seed.wrapping_add(i as u64))))
+    }
+
+    fn rows<'py, T: numpy::Element>(
+        &self,
+        py: Python<'py>,
+        values: Vec<T>,
+    ) -> Bound<'py, PyArray2<T>> {
+        let n = self.cores.len();
+        let width = values.len() / n;
+        // the batch holds as many rows of the same width as there are games
+        Array2::from_shape_vec((n, width), values)
+            .unwrap()
+            .into_pyarray(py)
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyVecGame {
+    /// `n_games` games of `settings`, game `i` being seeded with `seed + i` when a seed is given
+    #[new]
+    #[pyo3(signature = (settings, n_games, pot_fractions=vec![0.5, 1.0, 2.0], max_history=ObservationEncoder::DEFAULT_MAX_HISTORY, seed=None))]
+    pub fn new(
+        settings: &PySettings,
+        n_games: usize,
+        pot_fractions: Vec<f64>,
+        max_history: usize,
+        seed: Option<u64>,
+    ) -> PyResult<Self> {
+        if n_games == 0 {
+            return Err(PyValueError::new_err("a batch needs one game or more"));
+        }
+        let cores = (0..n_games)
+            .map(|_| EnvCore::new(settings, pot_fractions.clone(), max_history))
+            .collect::<PyResult<Vec<_>>>()?;
+        let mut vec_game = PyVecGame { cores };
+        if seed.is_some() {
+            vec_game.reseed(seed)?;
+        }
+        Ok(vec_game)
+    }
+
+    pub fn __len__(&self) -> PyResult<usize> {
+        Ok(self.cores.len())
+    }
+
+    /// Length of the observations, see `PyObservation.encode`
+    #[getter]
+    pub fn observation_size(&self) -> PyResult<usize> {
+        Ok(self.cores[0].encoder.size())
+    }
+
+    /// Length of the action masks, actions being indexed as in `PyPokerEnv`
+    #[getter]
+    pub fn n_actions(&self) -> PyResult<usize> {
+        Ok(self.cores[0].n_actions())
+    }
+
+    /// Deals a new hand in every game
+    ///
+    /// Returns the observations, the action masks and the seats to act.
+    #[pyo3(signature = (seed=None))]
+    pub fn reset<'py>(&mut self, py: Python<'py>, seed: Option<u64>) -> PyResult<Reset<'py>> {
+        self.reseed(seed)?;
+        let mut batch = self.batch();
+        py.detach(|| self.observe(&mut batch))?;
+        Ok((
+            self.rows(py, batch.observations),
+            self.rows(py, batch.action_masks),
+            batch.seats.into_pyarray(py),
+        ))
+    }
+
+    /// Plays one action in every game, given as an int64 array of action indices
+    ///
+    /// Returns the observations, the action masks, the rewards of every seat in big blinds,
+    /// whether each hand ended and the seats to act. The observations of the games whose hand
+    /// ended are the ones of the next hand. Raises `InvalidActionError` without playing any
+    /// action when an action mask does not allow the action of its game.
+    pub fn step<'py>(
+        &mut self,
+        py: Python<'py>,
+        actions: PyReadonlyArray1<'py, i64>,
+    ) -> PyResult<Step<'py>> {
+        let actions = actions.as_slice()?;
+        if actions.len() != self.cores.len() {
+            return Err(PyValueError::new_err(format!(
+                "{} actions for {} games",
+                actions.len(),
+                self.cores.len()
+            )));
+        }
+        let n_players = self.cores[0].settings.n_players;
+        let mut batch = self.batch();
+        py.detach(|| -> PyResult<()> {
+            let played = self
+                .cores
+                .par_iter()
+                .zip(actions)
+                .map(|(core, &action)| {
+                    core.action(
+                        core.game.current_seat(),
+                        usize::try_from(action).unwrap_or(usize::MAX),
+                    )
+                })
+                .collect::<PyResult<Vec<Action>>>()?;
+            self.cores
+                .par_iter_mut()
+                .zip(played)
+                .zip(batch.rewards.par_chunks_mut(n_players))
+                .zip(batch.dones.par_iter_mut())
+                .try_for_each(|(((core, action), rewards), done)| -> PyResult<()> {
+                    core.game.play_turn(action)?;
+                    if core.hand_over() {
+                        for (seat, reward) in rewards.iter_mut().enumerate() {
+                            *reward = core.reward(seat) as f32;
+                        }
+                        *done = true;
+                        core.reset(None)?;
+                    }
+                    Ok(())
+                })?;
+            self.observe(&mut batch)?;
+            Ok(())
+        })?;
+        Ok((
+            self.rows(py, batch.observations),
+            self.rows(py, batch.action_masks),
+            self.rows(py, batch.rewards),
+            batch.dones.into_pyarray(py).into_any(),
+            batch.seats.into_pyarray(py),
+        ))
+    }
+}
@@ -0,0 +1,363 @@
+use crate::*;
+use rand::{RngCore, seq::SliceRandom};
+
+/// Groups starting hands into buckets of similar strength
+///
+/// A bucket is dealt once per hand and holds for every round, the showdown being decided by the
+/// equity of one bucket against the other.
+#[derive(Clone, Debug)]
+pub struct CardAbstraction {
+    /// Bucket of every hand class, indexed by `HandClass::index`
+    class_buckets: Vec<usize>,
+    n_buckets: usize,
+    /// Probability of the two players being dealt each pair of buckets, row-major
+    joint: Vec<f64>,
+    /// Equity of the first player's bucket against the second player's, row-major
+    equity: Vec<f64>,
+}
+
+impl CardAbstraction {
+    pub fn new(class_buckets: Vec<usize>, joint: Vec<f64>, equity: Vec<f64>) -> Self {
+        let n_buckets = (joint.len() as f64).sqrt().round() as usize;
+        assert_eq!(n_buckets * n_buckets, joint.len());
+        assert_eq!(joint.len(), equity.len());
+        CardAbstraction {
+            class_buckets,
+            n_buckets,
+            joint,
+            equity,
+        }
+    }
+
+    /// Buckets hand classes by their equity against a random hand, each bucket holding about
+    /// the same number of hands, then samples deals to estimate how often each pair of buckets
+    /// is dealt and how it fares at showdown
+    pub fn estimate(
+        n_buckets: usize,
+        equity_samples: usize,
+        deal_samples: usize,
+        rng: &mut impl RngCore,
+    ) -> Self {
+        let equities = preflop_equities(equity_samples, rng);
+        let mut classes: Vec<HandClass> = HandClass::all().collect();
+        classes.sort_by(|a, b| equities[a.index()].total_cmp(&equities[b.index()]));
+
+        let mut class_buckets = vec![0; HandClass::COUNT];
+        let mut seen = 0;
+        for class in classes {
+            class_buckets[class.index()] = seen * n_buckets / 1326;
+            seen += class.combos();
+        }
+
+        let mut joint = vec![0.0; n_buckets * n_buckets];
+        let mut won = vec![0.0; n_buckets * n_buckets];
+        let mut deck: Vec<Card> = (0..52).map(Card::from_index).collect();
+        for _ in 0..deal_samples {
+            let (cards, _) = deck.partial_shuffle(rng, 9);
+            let hands = [
+                PlayerHand::new(cards[0], cards[1]),
+                PlayerHand::new(cards[2], cards[3]),
+            ];
+            let board = &cards[4..];
+            let i = class_buckets[HandClass::from_hand(&hands[0]).index()] * n_buckets
+                + class_buckets[HandClass::from_hand(&hands[1]).index()];
+
+            joint[i] += 1.0;
+            won[i] += match HandRank::evaluate(hands[0].cards().chain(board))
+                .cmp(&HandRank::evaluate(hands[1].cards().chain(board)))
+            {
+                std::cmp::Ordering::Greater => 1.0,
+                std::cmp::Ordering::Equal => 0.5,
+                std::cmp::Ordering::Less => 0.0,
+            };
+        }
+
+        let equity = joint
+            .iter()
+            .zip(&won)
+            .map(|(&n, &w)| if n > 0.0 { w / n } else { 0.5 })
+            .collect();
+        let joint = joint.iter().map(|n| n / deal_samples as f64).collect();
+
+        CardAbstraction::new(class_buckets, joint, equity)
+    }
+
+    pub fn n_buckets(&self) -> usize {
+        self.n_buckets
+    }
+
+    pub fn class_buckets(&self) -> &[usize] {
+        &self.class_buckets
+    }
+
+    pub fn bucket(&self, hand: &PlayerHand) -> usize {
+        self.class_buckets[HandClass::from_hand(hand).index()]
+    }
+
+    /// Probability of `player` being dealt `bucket` while the opponent is dealt `opponent_bucket`
+    pub fn joint_probability(&self, player: usize, bucket: usize, opponent_bucket: usize) -> f64 {
+        match player {
+            0 => self.joint[bucket * self.n_buckets + opponent_bucket],
+            _ => self.joint[opponent_bucket * self.n_buckets + bucket],
+        }
+    }
+
+    /// Share of the pot won by `player` holding `bucket` against `opponent_bucket`
+    pub fn equity(&self, player: usize, bucket: usize, opponent_bucket: usize) -> f64 {
+        match player {
+            0 => self.equity[bucket * self.n_buckets + opponent_bucket],
+            _ => 1.0 - self.equity[opponent_bucket * self.n_buckets + bucket],
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AbstractAction {
+    Fold,
+    /// Check, or call when facing a bet
+    Call,
+    /// Raise by a fraction of the pot after calling
+    PotRaise(f64),
+    AllIn,
+}
+
+/// Node of the heads-up betting tree, player 0 being the small blind
+#[derive(Clone, Debug)]
+pub enum Node {
+    Decision {
+        player: usize,
+        round: Round,
+        /// Actions with the node they lead to
+        actions: Vec<(AbstractAction, usize)>,
+    },
+    Fold {
+        player: usize,
+        contributions: [usize; 2],
+    },
+    Showdown {
+        contributions: [usize; 2],
+    },
+}
+
+/// Heads-up hold'em reduced to a few card buckets and bet sizes, small enough to be solved and
+/// evaluated exactly
+pub struct AbstractHoldem {
+    settings: Settings,
+    cards: CardAbstraction,
+    bets: BetAbstraction,
+    nodes: Vec<Node>,
+}
+
+impl AbstractHoldem {
+    pub fn new(settings: &Settings, cards: CardAbstraction, bets: &BetAbstraction) -> Self {
+        let mut builder = TreeBuilder {
+            nodes: Vec::new(),
+            stack: settings.initial_stack.get(),
+            big_blind: settings.big_blind.get(),
+            bets,
+        };
+        let blinds = [
+            settings.small_blind.min(settings.initial_stack).get(),
+            settings.big_blind.min(settings.initial_stack).get(),
+        ];
+        builder.build(BettingState {
+            round: Round::PreFlop,
+            contributions: blinds,
+            bets: blinds,
+            player: 0,
+            acted: [false; 2],
+            raises: 0,
+            last_raise: settings.big_blind.get(),
+        });
+
+        AbstractHoldem {
+            settings: settings.clone(),
+            cards,
+            bets: bets.clone(),
+            nodes: builder.nodes,
+        }
+    }
+
+    pub fn root(&self) -> usize {
+        0
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    pub fn node(&self, node: usize) -> &Node {
+        &self.nodes[node]
+    }
+
+    pub fn cards(&self) -> &CardAbstraction {
+        &self.cards
+    }
+
+    pub fn bets(&self) -> &BetAbstraction {
+        &self.bets
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn big_blind(&self) -> Chips {
+        self.settings.big_blind
+    }
+}
+
+#[derive(Clone, Copy)]
+struct BettingState {
+    round: Round,
+    contributions: [usize; 2],
+    bets: [usize; 2],
+    player: usize,
+    acted: [bool; 2],
+    raises: usize,
+    last_raise: usize,
+}
+
+struct TreeBuilder<'a> {
+    nodes: Vec<Node>,
+    stack: usize,
+    big_blind: usize,
+    bets: &'a BetAbstraction,
+}
+
+impl TreeBuilder<'_> {
+    fn build(&mut self, state: BettingState) -> usize {
+        let id = self.nodes.len();
+        self.nodes.push(Node::Showdown {
+            contributions: state.contributions,
+        });
+
+        let player = state.player;
+        let opponent = 1 - player;
+        let to_call = state.bets[opponent] - state.bets[player];
+        let remaining = self.stack - state.contributions[player];
+        let situation = BetSituation {
+            pot: Chips::new(state.contributions[0] + state.contributions[1]),
+            call_amount: Chips::new(to_call),
+            raise_range: (remaining > to_call).then(|| {
+                (
+                    Chips::new(state.last_raise.min(remaining - to_call)),
+                    Chips::new(remaining - to_call),
+                )
+            }),
+            raises: state.raises,
+        };
+
+        let mut actions = Vec::new();
+        for action in self.bets.abstract_actions(&situation) {
+            let child = match self.bets.to_action(action, &situation) {
+                Action::Fold => {
+                    self.nodes.push(Node::Fold {
+                        player,
+                        contributions: state.contributions,
+                    });
+                    self.nodes.len() - 1
+                }
+                Action::Check | Action::Call => {
+                    let mut call = Self::put_in(state, to_call);
+                    if call.acted[opponent] {
+                        self.end_round(call)
+                    } else {
+                        call.player = opponent;
+                        self.build(call)
+                    }
+                }
+                Action::Raise(raise) => {
+                    let raise = raise.get();
+                    let mut next = Self::put_in(state, to_call + raise);
+                    next.player = opponent;
+                    next.raises += 1;
+                    next.last_raise = next.last_raise.max(raise);
+                    self.build(next)
+                }
+            };
+            actions.push((action, child));
+        }
+
+        self.nodes[id] = Node::Decision {
+            player,
+            round: state.round,
+            actions,
+        };
+        id
+    }
+
+    fn put_in(state: BettingState, amount: usize) -> BettingState {
+        let mut next = state;
+        next.contributions[state.player] += amount;
+        next.bets[state.player] += amount;
+        next.acted[state.player] = true;
+        next
+    }
+
+    fn end_round(&mut self, state: BettingState) -> usize {
+        let all_in = state.contributions.contains(&self.stack);
+        match state.round.next() {
+            Some(round) if !all_in => self.build(BettingState {
+                round,
+                contributions: state.contributions,
+                bets: [0; 2],
+                // the big blind acts first after the flop
+                player: 1,
+                acted: [false; 2],
+                raises: 0,
+                last_raise: self.big_blind,
+            }),
+            _ => {
+                self.nodes.push(Node::Showdown {
+                    contributions: state.contributions,
+                });
+                self.nodes.len() - 1
+            }
+        }
+    }
+}
+
+/// Probability of every action at every decision node of an `AbstractHoldem`, for each bucket
+/// of the acting player
+#[derive(Clone, Debug)]
+pub struct StrategyProfile {
+    n_buckets: usize,
+    /// Bucket-major action probabilities of every node, empty for terminal nodes
+    probabilities: Vec<Vec<f64>>,
+}
+
+impl StrategyProfile {
+    pub fn uniform(game: &AbstractHoldem) -> Self {
+        let n_buckets = game.cards().n_buckets();
+        let probabilities = game
+            .nodes()
+            .iter()
+            .map(|node| match node {
+                Node::Decision { actions, .. } => {
+                    vec![1.0 / actions.len() as f64; actions.len() * n_buckets]
+                }
+                _ => Vec::new(),
+            })
+            .collect();
+        StrategyProfile {
+            n_buckets,
+            probabilities,
+        }
+    }
+
+    pub fn n_buckets(&self) -> usize {
+        self.n_buckets
+    }
+
+    pub fn action_probabilities(&self, node: usize, bucket: usize) -> &[f64] {
+        let n_actions = self.probabilities[node].len() / self.n_buckets;
+        &self.probabilities[node][bucket * n_actions..(bucket + 1) * n_actions]
+    }
+
+    pub fn set_action_probabilities(&mut self, node: usize, bucket: usize, probabilities: &[f64]) {
+        let n_actions = self.probabilities[node].len() / self.n_buckets;
+        self.probabilities[node][bucket * n_actions..(bucket + 1) * n_actions]
+            .copy_from_slice(probabilities);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::solver::*;
+
+/// Value for `player` of its best response against the strategy of the other player, in chips
+/// per game
+pub fn best_response_value(game: &AbstractHoldem, profile: &StrategyProfile, player: usize) -> f64 {
+    let opponent_reach = vec![1.0; game.cards().n_buckets()];
+    values(
+        game,
+        profile,
+        player,
+        game.root(),
+        &opponent_reach,
+        &mut Respond::Best(None),
+    )
+    .iter()
+    .sum()
+}
+
+/// Copy of `profile` where `player` plays its best response against the other player
+pub fn best_response(
+    game: &AbstractHoldem,
+    profile: &StrategyProfile,
+    player: usize,
+) -> StrategyProfile {
+    let mut response = profile.clone();
+    let opponent_reach = vec![1.0; game.cards().n_buckets()];
+    values(
+        game,
+        profile,
+        player,
+        game.root(),
+        &opponent_reach,
+        &mut Respond::Best(Some(&mut response)),
+    );
+    response
+}
+
+/// Value for `player` when both players follow `profile`, in chips per game
+pub fn expected_value(game: &AbstractHoldem, profile: &StrategyProfile, player: usize) -> f64 {
+    let opponent_reach = vec![1.0; game.cards().n_buckets()];
+    values(
+        game,
+        profile,
+        player,
+        game.root(),
+        &opponent_reach,
+        &mut Respond::Follow,
+    )
+    .iter()
+    .sum()
+}
+
+/// How much a best response wins on average against `profile`, in milli big blinds per game
+///
+/// Zero for a Nash equilibrium.
+pub fn exploitability(game: &AbstractHoldem, profile: &StrategyProfile) -> f64 {
+    let total = best_response_value(game, profile, 0) + best_response_value(game, profile, 1);
+    total / 2.0 / game.big_blind().as_f64() * 1000.0
+}
+
+enum Respond<'a> {
+    /// Play the best action, recording it in the profile if there is one
+    Best(Option<&'a mut StrategyProfile>),
+    Follow,
+}
+
+/// Value for `player` of the subtree below `node` for each of its buckets, weighted by the
+/// probability of the buckets being dealt and of the opponent reaching the node
+fn values(
+    game: &AbstractHoldem,
+    profile: &StrategyProfile,
+    player: usize,
+    node: usize,
+    opponent_reach: &[f64],
+    respond: &mut Respond,
+) -> Vec<f64> {
+    let cards = game.cards();
+    let n_buckets = cards.n_buckets();
+    let opponent = 1 - player;
+
+    let terminal = |utility: &dyn Fn(usize, usize) -> f64| -> Vec<f64> {
+        (0..n_buckets)
+            .map(|bucket| {
+                (0..n_buckets)
+                    .map(|other| {
+                        cards.joint_probability(player, bucket, other)
+                            * opponent_reach[other]
+                            * utility(bucket, other)
+                    })
+                    .sum()
+            })
+            .collect()
+    };
+
+    match game.node(node) {
+        Node::Fold {
+            player: folder,
+            contributions,
+        } => {
+            let utility = if *folder == player {
+                -(contributions[player] as f64)
+            } else {
+                contributions[opponent] as f64
+            };
+            terminal(&|_, _| utility)
+        }
+        Node::Showdown { contributions } => terminal(&|bucket, other| {
+            let equity = cards.equity(player, bucket, other);
+            equity * contributions[opponent] as f64 - (1.0 - equity) * contributions[player] as f64
+        }),
+        Node::Decision {
+            player: acting,
+            actions,
+            ..
+        } if *acting == player => {
+            let children: Vec<Vec<f64>> = actions
+                .iter()
+                .map(|&(_, child)| values(game, profile, player, child, opponent_reach, respond))
+                .collect();
+
+            (0..n_buckets)
+                .map(|bucket| match respond {
+                    Respond::Best(response) => {
+                        let (best, value) = children
+                            .iter()
+                            .map(|child| child[bucket])
+                            .enumerate()
+                            .max_by(|a, b| a.1.total_cmp(&b.1))
+                            .unwrap();
+                        if let Some(response) = response {
+                            let mut probabilities = vec![0.0; actions.len()];
+                            probabilities[best] = 1.0;
+                            response.set_action_probabilities(node, bucket, &probabilities);
+                        }
+                        value
+                    }
+                    Respond::Follow => profile
+                        .action_probabilities(node, bucket)
+                        .iter()
+                        .zip(&children)
+                        .map(|(p, child)| p * child[bucket])
+                        .sum(),
+                })
+                .collect()
+        }
+        Node::Decision { actions, .. } => {
+            let mut total = vec![0.0; n_buckets];
+            for (a, &(_, child)) in actions.iter().enumerate() {
+                let reach: Vec<f64> = (0..n_buckets)
+                    .map(|other| {
+                        opponent_reach[other] * profile.action_probabilities(node, other)[a]
+                    })
+                    .collect();
+                if reach.iter().all(|&r| r == 0.0) {
+                    continue;
+                }
+                for (t, v) in total
+                    .iter_mut()
+                    .zip(values(game, profile, player, child, &reach, respond))
+                {
+                    *t += v;
+                }
+            }
+            total
+        }
+    }
+}
+
+#[test]
+fn best_response_to_a_folding_big_blind() {
+    let settings = Settings {
+        n_players: 2,
+        initial_stack: Chips::new(200),
+        small_blind: Chips::new(1),
+        big_blind: Chips::new(2),
+        ..Default::default()
+    };
+    // the second bucket always wins
+    let cards = CardAbstraction::new(
+        vec![0; HandClass::COUNT],
+        vec![0.0, 0.5, 0.5, 0.0],
+        vec![0.5, 0.0, 1.0, 0.5],
+    );
+    let bets = BetAbstraction {
+        pot_fractions: vec![0.5, 1.0],
+        max_raises: 2,
+        all_in: true,
+    };
+    let game = AbstractHoldem::new(&settings, cards, &bets);
+
+    // the big blind folds to any raise and checks or calls otherwise
+    let mut profile = StrategyProfile::uniform(&game);
+    for (id, node) in game.nodes().iter().enumerate() {
+        if let Node::Decision {
+            player: 1, actions, ..
+        } = node
+        {
+            let facing_raise = actions[0].0 == AbstractAction::Fold;
+            let probabilities: Vec<f64> = actions
+                .iter()
+                .map(|(action, _)| match action {
+                    AbstractAction::Fold if facing_raise => 1.0,
+                    AbstractAction::Call if !facing_raise => 1.0,
+                    _ => 0.0,
+                })
+                .collect();
+            for bucket in 0..2 {
+                profile.set_action_probabilities(id, bucket, &probabilities);
+            }
+        }
+    }
+
+    // raising wins the big blind every time, nothing can win more
+    assert_eq!(best_response_value(&game, &profile, 0), 2.0);
+    let response = best_response(&game, &profile, 0);
+    assert_eq!(expected_value(&game, &response, 0), 2.0);
+    assert!(exploitability(&game, &StrategyProfile::uniform(&game)) > 0.0);
+}
@@ -0,0 +1,209 @@
+use crate::solver::*;
+use rand::{Rng, RngCore};
+
+/// Reduces the raises of the game to a few sizes relative to the pot
+#[derive(Clone, Debug, PartialEq)]
+pub struct BetAbstraction {
+    /// Raise sizes as fractions of the pot after calling
+    pub pot_fractions: Vec<f64>,
+    /// Sized raises allowed per round, going all-in stays possible afterwards
+    pub max_raises: usize,
+    pub all_in: bool,
+}
+
+/// Sizes fitting the stack depth: shallow stacks only go all-in, deeper ones get more sizes
+impl From<&Settings> for BetAbstraction {
+    fn from(settings: &Settings) -> Self {
+        let depth = settings.initial_stack.get() / settings.big_blind.get().max(1);
+        let pot_fractions = match depth {
+            0..=20 => vec![],
+            21..=50 => vec![0.5, 1.0],
+            _ => vec![0.5, 1.0, 2.0],
+        };
+        BetAbstraction {
+            pot_fractions,
+            max_raises: 3,
+            all_in: true,
+        }
+    }
+}
+
+/// Bets and stacks as seen by the seat to act
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BetSituation {
+    /// Chips in the middle, including the bets of the current round
+    pub pot: Chips,
+    pub call_amount: Chips,
+    /// Smallest and largest amount a raise can add on top of the call
+    pub raise_range: Option<(Chips, Chips)>,
+    /// Raises already made in the round
+    pub raises: usize,
+}
+
+impl From<&ObservableState> for BetSituation {
+    fn from(state: &ObservableState) -> Self {
+        let legal_actions = state.legal_actions();
+        BetSituation {
+            pot: state.total_pot(),
+            call_amount: legal_actions.call_amount,
+            raise_range: legal_actions.raise_range,
+            raises: state
+                .history
+                .iter()
+                .filter(|record| {
+                    record.round == state.round && matches!(record.action, Action::Raise(_))
+                })
+                .count(),
+        }
+    }
+}
+
+impl BetSituation {
+    /// Raise amount as a fraction of the pot after calling
+    fn pot_fraction(&self, amount: Chips) -> f64 {
+        amount.as_f64() / (self.pot + self.call_amount).get().max(1) as f64
+    }
+}
+
+impl BetAbstraction {
+    /// Abstract actions available in the situation, each raise leading to a distinct amount
+    pub fn abstract_actions(&self, situation: &BetSituation) -> Vec<AbstractAction> {
+        let mut actions = Vec::new();
+        if !situation.call_amount.is_zero() {
+            actions.push(AbstractAction::Fold);
+        }
+        actions.push(AbstractAction::Call);
+
+        let Some((min, max)) = situation.raise_range else {
+            return actions;
+        };
+        if situation.raises < self.max_raises {
+            let mut amounts = Vec::new();
+            for &fraction in &self.pot_fractions {
+                let amount = self.raise_amount(fraction, situation);
+                if amount >= min && amount < max && !amounts.contains(&amount) {
+                    amounts.push(amount);
+                    actions.push(AbstractAction::PotRaise(fraction));
+                }
+            }
+        }
+        if self.all_in {
+            actions.push(AbstractAction::AllIn);
+        }
+        actions
+    }
+
+    pub fn raise_amount(&self, pot_fraction: f64, situation: &BetSituation) -> Chips {
+        Chips::new(
+            (pot_fraction * (situation.pot + situation.call_amount).as_f64()).round() as usize,
+        )
+    }
+
+    pub fn to_action(&self, action: AbstractAction, situation: &BetSituation) -> Action {
+        let (min, max) = situation.raise_range.unwrap_or_default();
+        match action {
+            AbstractAction::Fold => Action::Fold,
+            AbstractAction::Call if situation.call_amount.is_zero() => Action::Check,
+            AbstractAction::Call => Action::Call,
+            AbstractAction::PotRaise(fraction) => {
+                Action::Raise(self.raise_amount(fraction, situation).clamp(min, max))
+            }
+            AbstractAction::AllIn => Action::Raise(max),
+        }
+    }
+
+    /// Maps a real action onto the abstract actions of the situation, with probabilities
+    ///
+    /// Raises between two abstract sizes are split with the pseudo-harmonic mapping of Ganzfried
+    /// and Sandholm, raises outside of the abstract sizes go to the closest one.
+    pub fn translate(
+        &self,
+        action: Action,
+        situation: &BetSituation,
+    ) -> Vec<(AbstractAction, f64)> {
+        let raise = match action {
+            Action::Fold => return vec![(AbstractAction::Fold, 1.0)],
+            Action::Check | Action::Call => return vec![(AbstractAction::Call, 1.0)],
+            Action::Raise(raise) => raise,
+        };
+
+        let sizes: Vec<(AbstractAction, f64)> = self
+            .abstract_actions(situation)
+            .into_iter()
+            .filter_map(
+                |abstract_action| match self.to_action(abstract_action, situation) {
+                    Action::Raise(amount) => {
+                        Some((abstract_action, situation.pot_fraction(amount)))
+                    }
+                    _ => None,
+                },
+            )
+            .collect();
+        let x = situation.pot_fraction(raise);
+
+        let below = sizes.iter().rev().find(|(_, size)| *size <= x);
+        let above = sizes.iter().find(|(_, size)| *size >= x);
+        match (below, above) {
+            (Some(&(a_action, a)), Some(&(b_action, b))) if a < b => {
+                let p = (b - x) * (1.0 + a) / ((b - a) * (1.0 + x));
+                vec![(a_action, p), (b_action, 1.0 - p)]
+            }
+            (Some(&(action, _)), _) | (None, Some(&(action, _))) => vec![(action, 1.0)],
+            (None, None) => vec![(AbstractAction::Call, 1.0)],
+        }
+    }
+
+    /// Draws one abstract action from the translation of a real action
+    pub fn translate_sampled(
+        &self,
+        action: Action,
+        situation: &BetSituation,
+        rng: &mut impl RngCore,
+    ) -> AbstractAction {
+        let translation = self.translate(action, situation);
+        let mut target = rng.random::<f64>();
+        for &(abstract_action, probability) in &translation {
+            if target < probability {
+                return abstract_action;
+            }
+            target -= probability;
+        }
+        translation[translation.len() - 1].0
+    }
+}
+
+#[test]
+fn pseudo_harmonic_translation() {
+    let bets = BetAbstraction {
+        pot_fractions: vec![0.5, 1.0],
+        max_raises: 3,
+        all_in: true,
+    };
+    let situation = BetSituation {
+        pot: Chips::new(100),
+        call_amount: Chips::ZERO,
+        raise_range: Some((Chips::new(10), Chips::new(1000))),
+        raises: 0,
+    };
+    assert_eq!(
+        bets.abstract_actions(&situation),
+        vec![
+            AbstractAction::Call,
+            AbstractAction::PotRaise(0.5),
+            AbstractAction::PotRaise(1.0),
+            AbstractAction::AllIn
+        ]
+    );
+
+    assert_eq!(
+        bets.translate(Action::Raise(Chips::new(100)), &situation),
+        vec![(AbstractAction::PotRaise(1.0), 1.0)]
+    );
+    let translation = bets.translate(Action::Raise(Chips::new(75)), &situation);
+    assert_eq!(translation[0].0, AbstractAction::PotRaise(0.5));
+    assert!((translation[0].1 - 3.0 / 7.0).abs() < 1e-9);
+    assert_eq!(
+        bets.translate(Action::Raise(Chips::new(5000)), &situation),
+        vec![(AbstractAction::AllIn, 1.0)]
+    );
+}
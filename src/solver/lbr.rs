@@ -0,0 +1,253 @@
+use crate::solver::*;
+use rand::RngCore;
+
+/// Local best response (Lisý and Bowling, 2017), a heads-up exploiter for the unabstracted game
+///
+/// It tracks the range of the opponent from its policy and, at each decision, takes the action
+/// with the best value assuming both players only check or call to the showdown afterwards.
+/// Its winnings are a lower bound on the exploitability of the opponent.
+pub struct LocalBestResponse {
+    /// Raises tried at every decision
+    pub bets: BetAbstraction,
+    /// Monte Carlo samples used for every equity estimate
+    pub equity_samples: usize,
+}
+
+impl LocalBestResponse {
+    pub fn new(settings: &Settings) -> Self {
+        LocalBestResponse {
+            bets: settings.into(),
+            equity_samples: 200,
+        }
+    }
+
+    /// Average winnings against `opponent` over `hands` heads-up hands, alternating seats, in
+    /// milli big blinds per game
+    ///
+    /// The cards are dealt from `rng` too, so the same seed gives the same estimate.
+    pub fn evaluate(
+        &self,
+        settings: &Settings,
+        opponent: &impl Policy,
+        hands: usize,
+        rng: &mut impl RngCore,
+    ) -> Result<f64, GameError> {
+        let mut total = 0;
+        for hand in 0..hands {
+            total += self.play_hand(settings, opponent, hand % 2, rng)?;
+        }
+        Ok(total as f64 / hands as f64 / settings.big_blind.as_f64() * 1000.0)
+    }
+
+    fn play_hand(
+        &self,
+        settings: &Settings,
+        opponent: &impl Policy,
+        seat: usize,
+        rng: &mut impl RngCore,
+    ) -> Result<isize, GameError> {
+        let mut game = Game::with_seed(
+            Settings {
+                n_players: 2,
+                ..settings.clone()
+            },
+            rng.next_u64(),
+        )?;
+        let hand = game
+            .get_observable_state(seat)
+            .hand
+            .ok_or(GameError::InvalidAction)?;
+        let mut range: Vec<(PlayerHand, f64)> = PlayerHand::all()
+            .into_iter()
+            .filter(|h| !h.conflicts(&hand))
+            .map(|h| (h, 1.0))
+            .collect();
+
+        while game.hand_count() == 0 {
+            let state = game.get_observable_state(game.current_seat());
+            let action = if state.seat == seat {
+                self.choose_action(&state, &range, opponent, rng)
+            } else {
+                let action = sample_action(&opponent.action_probabilities(&state), rng)
+                    .ok_or(GameError::InvalidAction)?;
+                Self::update_range(&mut range, &state, action, opponent);
+                action
+            };
+            game.play_turn(action)?;
+        }
+
+        Ok(game
+            .last_hand_result()
+            .map_or(0, |result| result.deltas[seat]))
+    }
+
+    /// Weights every hand of the range by the probability of the opponent taking `action` with it
+    fn update_range(
+        range: &mut [(PlayerHand, f64)],
+        state: &ObservableState,
+        action: Action,
+        opponent: &impl Policy,
+    ) {
+        let mut hypothetical = state.clone();
+        for (hand, weight) in range.iter_mut() {
+            if *weight == 0.0 {
+                continue;
+            }
+            if state.board.iter().any(|c| hand.contains(c)) {
+                *weight = 0.0;
+                continue;
+            }
+            hypothetical.hand = Some(*hand);
+            *weight *= opponent
+                .action_probabilities(&hypothetical)
+                .iter()
+                .filter(|(a, _)| *a == action)
+                .map(|(_, p)| p)
+                .sum::<f64>();
+        }
+    }
+
+    fn choose_action(
+        &self,
+        state: &ObservableState,
+        range: &[(PlayerHand, f64)],
+        opponent: &impl Policy,
+        rng: &mut impl RngCore,
+    ) -> Action {
+        let legal_actions = state.legal_actions();
+        let Some(hand) = state.hand else {
+            return Action::Fold;
+        };
+        let equity = equity_vs_range(&hand, &state.board, range, self.equity_samples, rng);
+        let pot = state.total_pot().as_f64();
+        let asked = legal_actions.call_amount.as_f64();
+
+        let (mut best_action, mut best_value) = if legal_actions.can_check() {
+            (Action::Check, equity * pot)
+        } else {
+            (Action::Call, equity * pot - (1.0 - equity) * asked)
+        };
+        if best_value < 0.0 {
+            (best_action, best_value) = (Action::Fold, 0.0);
+        }
+
+        for raise in self.raise_amounts(state) {
+            let fold_probability = Self::fold_probability(state, range, raise, opponent);
+            let called =
+                equity * (pot + raise.as_f64()) - (1.0 - equity) * (asked + raise.as_f64());
+            let value = fold_probability * pot + (1.0 - fold_probability) * called;
+            if value > best_value {
+                (best_action, best_value) = (Action::Raise(raise), value);
+            }
+        }
+
+        best_action
+    }
+
+    fn raise_amounts(&self, state: &ObservableState) -> Vec<Chips> {
+        let situation = BetSituation::from(state);
+        self.bets
+            .abstract_actions(&situation)
+            .into_iter()
+            .filter_map(|action| match self.bets.to_action(action, &situation) {
+                Action::Raise(amount) => Some(amount),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Probability of the opponent folding to a raise, over its range
+    fn fold_probability(
+        state: &ObservableState,
+        range: &[(PlayerHand, f64)],
+        raise: Chips,
+        opponent: &impl Policy,
+    ) -> f64 {
+        let opponent_seat = state
+            .seats
+            .iter()
+            .enumerate()
+            .position(|(i, seat)| i != state.seat && seat.is_valid())
+            .unwrap_or(state.seat);
+        let put_in = state.legal_actions().call_amount + raise;
+
+        let mut hypothetical = state.clone();
+        let seat = &mut hypothetical.seats[state.seat];
+        seat.stack -= put_in;
+        seat.bet += put_in;
+        seat.last_action_in_current_round = Some(Action::Raise(raise));
+        hypothetical.last_raise = hypothetical.last_raise.max(raise);
+        hypothetical.current_seat = opponent_seat;
+        hypothetical.seat = opponent_seat;
+
+        let mut folds = 0.0;
+        let mut total = 0.0;
+        for &(hand, weight) in range {
+            if weight == 0.0 || state.board.iter().any(|c| hand.contains(c)) {
+                continue;
+            }
+            hypothetical.hand = Some(hand);
+            folds += weight
+                * opponent
+                    .action_probabilities(&hypothetical)
+                    .iter()
+                    .filter(|(a, _)| *a == Action::Fold)
+                    .map(|(_, p)| p)
+                    .sum::<f64>();
+            total += weight;
+        }
+
+        if total > 0.0 { folds / total } else { 0.0 }
+    }
+}
+
+#[test]
+fn local_best_response_against_a_folder() {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    struct Folder;
+    impl Policy for Folder {
+        fn action_probabilities(&self, state: &ObservableState) -> Vec<(Action, f64)> {
+            if state.legal_actions().can_check() {
+                vec![(Action::Check, 1.0)]
+            } else {
+                vec![(Action::Fold, 1.0)]
+            }
+        }
+    }
+
+    let settings = Settings {
+        n_players: 2,
+        initial_stack: Chips::new(1000),
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        ..Default::default()
+    };
+    let lbr = LocalBestResponse {
+        equity_samples: 20,
+        ..LocalBestResponse::new(&settings)
+    };
+
+    // steals the big blind as small blind and gets the small blind for free otherwise
+    let winnings = lbr
+        .evaluate(&settings, &Folder, 10, &mut StdRng::seed_from_u64(0))
+        .unwrap();
+    assert_eq!(winnings, 750.0);
+
+    // a calling station pays off every value bet, which wins more than 5 big blinds a hand
+    struct Caller;
+    impl Policy for Caller {
+        fn action_probabilities(&self, state: &ObservableState) -> Vec<(Action, f64)> {
+            if state.legal_actions().can_check() {
+                vec![(Action::Check, 1.0)]
+            } else {
+                vec![(Action::Call, 1.0)]
+            }
+        }
+    }
+    let winnings = lbr
+        .evaluate(&settings, &Caller, 400, &mut StdRng::seed_from_u64(1))
+        .unwrap();
+    assert!(winnings > 5000.0, "{winnings}");
+}
@@ -0,0 +1,32 @@
+mod abstract_holdem;
+mod best_response;
+mod bet_abstraction;
+mod lbr;
+mod policy;
+mod push_fold;
+mod strategy;
+
+pub use abstract_holdem::*;
+pub use best_response::*;
+pub use bet_abstraction::*;
+pub use lbr::*;
+pub use policy::*;
+pub use push_fold::*;
+pub use strategy::*;
+
+use crate::*;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum StrategyError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Not a strategy file")]
+    InvalidFormat,
+    #[error("Unsupported strategy file version {0}")]
+    UnsupportedVersion(u32),
+    #[error("Strategy file checksum mismatch")]
+    ChecksumMismatch,
+    #[error("State is not part of the abstract game")]
+    OffTree,
+}
@@ -0,0 +1,25 @@
+use crate::*;
+use rand::{Rng, RngCore};
+
+/// A randomized strategy for the unabstracted game
+pub trait Policy {
+    /// Actions the policy may take in the state, with their probabilities
+    fn action_probabilities(&self, state: &ObservableState) -> Vec<(Action, f64)>;
+}
+
+/// Draws an action from a distribution, `None` if it is empty
+pub fn sample_action(probabilities: &[(Action, f64)], rng: &mut impl RngCore) -> Option<Action> {
+    let total: f64 = probabilities.iter().map(|(_, p)| p).sum();
+    let mut target = rng.random::<f64>() * total;
+    for &(action, probability) in probabilities {
+        if target < probability {
+            return Some(action);
+        }
+        target -= probability;
+    }
+    probabilities
+        .iter()
+        .rev()
+        .find(|(_, p)| *p > 0.0)
+        .map(|(action, _)| *action)
+}
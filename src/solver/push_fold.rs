@@ -0,0 +1,542 @@
+use crate::solver::*;
+
+/// Stacks, blinds and antes of a push/fold spot
+///
+/// Seats are given in the order they act preflop, so the last two are the small and the big
+/// blind, or the small blind first when heads-up. Every seat pays the ante, then the blinds are
+/// posted. Results are counted in chips, or in prize equity with `icm_equities` when payouts
+/// are given.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PushFoldSpot {
+    pub stacks: Vec<Chips>,
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    pub ante: Chips,
+    pub payouts: Option<Vec<f64>>,
+}
+
+impl PushFoldSpot {
+    /// Chips every seat has put in the pot before anyone acts, and the part of it that is a bet
+    fn posted(&self) -> Vec<(Chips, Chips)> {
+        let n = self.stacks.len();
+        self.stacks
+            .iter()
+            .enumerate()
+            .map(|(seat, &stack)| {
+                let ante = self.ante.min(stack);
+                let blind = match n - seat {
+                    2 => self.small_blind,
+                    1 => self.big_blind,
+                    _ => Chips::ZERO,
+                };
+                (ante + blind.min(stack - ante), blind.min(stack - ante))
+            })
+            .collect()
+    }
+
+    /// Stacks at the end of the hand when `jammer` goes all-in and `caller` calls, or when
+    /// nobody calls, the pot going to `winner`
+    ///
+    /// The part of the largest bet nobody matched, be it a jam or a blind bigger than a short
+    /// jam, goes back to its seat.
+    fn final_stacks(&self, jammer: usize, caller: Option<usize>, winner: usize) -> Vec<Chips> {
+        let posted = self.posted();
+        let antes: Vec<Chips> = posted.iter().map(|&(paid, blind)| paid - blind).collect();
+        let mut bets: Vec<Chips> = (0..self.stacks.len())
+            .map(|seat| match seat == jammer || Some(seat) == caller {
+                true => self.stacks[seat] - antes[seat],
+                false => posted[seat].1,
+            })
+            .collect();
+        let top = (0..bets.len()).max_by_key(|&seat| bets[seat]).unwrap_or(0);
+        let matched = (0..bets.len())
+            .filter(|&seat| seat != top)
+            .map(|seat| bets[seat])
+            .max()
+            .unwrap_or_default();
+        bets[top] = bets[top].min(matched);
+
+        let pot = antes.iter().sum::<Chips>() + bets.iter().sum::<Chips>();
+        let mut stacks: Vec<Chips> = (0..self.stacks.len())
+            .map(|seat| self.stacks[seat] - antes[seat] - bets[seat])
+            .collect();
+        stacks[winner] += pot;
+        stacks
+    }
+
+    /// Value of the final stacks for every seat
+    fn utilities(&self, stacks: &[Chips]) -> Vec<f64> {
+        match &self.payouts {
+            Some(payouts) => icm_equities(stacks, payouts),
+            None => stacks.iter().map(|stack| stack.as_f64()).collect(),
+        }
+    }
+}
+
+/// Frequency a decision is taken with for every hand class
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PushFoldChart {
+    /// Indexed by `HandClass::index`
+    frequencies: Vec<f64>,
+}
+
+impl PushFoldChart {
+    pub fn frequency(&self, class: HandClass) -> f64 {
+        self.frequencies[class.index()]
+    }
+
+    /// Whether the class jams or calls, that is whether it does more often than not
+    pub fn plays(&self, class: HandClass) -> bool {
+        self.frequency(class) >= 0.5
+    }
+
+    /// Share of the two card hands that play
+    pub fn range_size(&self) -> f64 {
+        HandClass::all()
+            .filter(|class| self.plays(*class))
+            .map(|class| class.combos())
+            .sum::<usize>() as f64
+            / 1326.0
+    }
+
+    /// Frequencies in the usual 13x13 grid, see `HandClass::index`
+    pub fn grid(&self) -> [[f64; 13]; 13] {
+        let mut grid = [[0.0; 13]; 13];
+        for (index, &frequency) in self.frequencies.iter().enumerate() {
+            grid[index / 13][index % 13] = frequency;
+        }
+        grid
+    }
+}
+
+/// The 13x13 grid, the classes that play by name and the others as dots
+impl std::fmt::Display for PushFoldChart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in 0..13 {
+            let cells: Vec<String> = (0..13)
+                .map(|column| HandClass::from_index(row * 13 + column))
+                .map(|class| match self.plays(class) {
+                    true => format!("{:<4}", class.to_string()),
+                    false => format!("{:<4}", "."),
+                })
+                .collect();
+            writeln!(f, "{}", cells.concat().trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Jam and call charts of every seat at a push/fold equilibrium
+///
+/// Every seat folded to may only jam or fold, and every seat facing a jam may only call or
+/// fold. Once a jam is called the seats left fold, so pots are contested by two seats at most.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PushFoldSolution {
+    spot: PushFoldSpot,
+    /// Chart of every seat but the big blind when folded to
+    jams: Vec<PushFoldChart>,
+    /// Chart of every seat after the jammer, indexed by `call_index`
+    calls: Vec<PushFoldChart>,
+    values: Vec<f64>,
+}
+
+fn call_index(n: usize, jammer: usize, caller: usize) -> usize {
+    jammer * n + caller
+}
+
+impl PushFoldSolution {
+    pub fn spot(&self) -> &PushFoldSpot {
+        &self.spot
+    }
+
+    /// Chart of `seat` when every seat before it folded, `None` for the big blind
+    pub fn jam_chart(&self, seat: usize) -> Option<&PushFoldChart> {
+        self.jams.get(seat)
+    }
+
+    /// Chart of `seat` facing a jam of `jammer`, every seat in between having folded
+    pub fn call_chart(&self, jammer: usize, seat: usize) -> Option<&PushFoldChart> {
+        let n = self.spot.stacks.len();
+        (jammer < seat && seat < n).then(|| &self.calls[call_index(n, jammer, seat)])
+    }
+
+    /// Expected chips, or prize equity, of every seat when all of them follow the charts
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+}
+
+/// Plays the charts in a hand whose live seats match the spot, jamming by raising all-in
+///
+/// Falls back to checking or calling outside of them.
+impl Policy for PushFoldSolution {
+    fn action_probabilities(&self, state: &ObservableState) -> Vec<(Action, f64)> {
+        let n = state.seats.len();
+        // live seats in the order they act preflop, from the seat after the big blind
+        let mut order: Vec<usize> = (0..n)
+            .map(|i| (state.sb_seat + i) % n)
+            .filter(|&seat| !state.seats[seat].is_dead)
+            .collect();
+        let (Some(hand), Round::PreFlop, true) = (
+            state.hand,
+            state.round,
+            order.len() == self.spot.stacks.len(),
+        ) else {
+            return Vec::new();
+        };
+        let blinds = 2 % order.len();
+        order.rotate_left(blinds);
+        let position = |seat| order.iter().position(|&s| s == seat).unwrap_or(0);
+        let class = HandClass::from_hand(&hand);
+        let seat = position(state.seat);
+
+        let raises: Vec<&ActionRecord> = state
+            .history
+            .iter()
+            .filter(|record| matches!(record.action, Action::Raise(_)))
+            .collect();
+        let called = state
+            .history
+            .iter()
+            .any(|record| record.action == Action::Call);
+        let (action, chart) = match raises[..] {
+            [] => {
+                let legal_actions = state.legal_actions();
+                let all_in = state.seats[state.seat]
+                    .stack
+                    .saturating_sub(legal_actions.call_amount);
+                (Action::Raise(all_in), self.jam_chart(seat))
+            }
+            [jam] if !called => (Action::Call, self.call_chart(position(jam.seat), seat)),
+            _ => return vec![(Action::Fold, 1.0)],
+        };
+        match chart {
+            Some(chart) => {
+                let frequency = chart.frequency(class);
+                vec![(action, frequency), (Action::Fold, 1.0 - frequency)]
+            }
+            // the big blind, folded to
+            None => vec![(Action::Check, 1.0)],
+        }
+    }
+}
+
+/// Solves a push/fold spot by fictitious play
+///
+/// Every iteration, each seat best responds to the average charts of the others, the best
+/// response being taken class by class, and the averages move towards it. Equities come from
+/// `table`, card removal being accounted for between the jammer and the caller only.
+pub fn solve_push_fold(
+    spot: &PushFoldSpot,
+    table: &PreflopEquityTable,
+    iterations: usize,
+) -> Result<PushFoldSolution, GameError> {
+    let n = spot.stacks.len();
+    if n < 2
+        || spot.stacks.contains(&Chips::ZERO)
+        || spot.big_blind.is_zero()
+        || spot.small_blind > spot.big_blind
+    {
+        return Err(GameError::InvalidSettings);
+    }
+    let solver = PushFoldSolver::new(spot, table);
+    let mut jams = vec![vec![0.5; HandClass::COUNT]; n - 1];
+    let mut calls = vec![vec![0.5; HandClass::COUNT]; n * n];
+    let mut values = Vec::new();
+    for iteration in 0..iterations.max(1) {
+        let plan = solver.plan(&jams, &calls);
+        let step = 1.0 / (iteration + 2) as f64;
+        for (jammer, jam) in jams.iter_mut().enumerate() {
+            let fold = plan.opens[jammer + 1][jammer];
+            for (class, frequency) in jam.iter_mut().enumerate() {
+                let best = solver.jam_value(&calls, jammer, class) > fold;
+                *frequency += step * (best as u8 as f64 - *frequency);
+            }
+        }
+        for jammer in 0..n - 1 {
+            for caller in jammer + 1..n {
+                let fold = plan.tails[jammer][caller + 1][caller];
+                let call = &mut calls[call_index(n, jammer, caller)];
+                for (class, frequency) in call.iter_mut().enumerate() {
+                    let best = solver.call_value(&jams[jammer], jammer, caller, class) > fold;
+                    *frequency += step * (best as u8 as f64 - *frequency);
+                }
+            }
+        }
+        values = plan.opens[0].clone();
+    }
+
+    let chart = |frequencies: Vec<f64>| PushFoldChart { frequencies };
+    Ok(PushFoldSolution {
+        spot: spot.clone(),
+        jams: jams.into_iter().map(chart).collect(),
+        calls: calls.into_iter().map(chart).collect(),
+        values,
+    })
+}
+
+/// Values of the seats for every way the hand can end, with the equities of the table
+struct PushFoldSolver {
+    n: usize,
+    equity: Vec<f64>,
+    combos: Vec<f64>,
+    /// Everyone folds to the big blind
+    walk: Vec<f64>,
+    /// Nobody calls the jam of a seat
+    steals: Vec<Vec<f64>>,
+    /// The jammer wins or loses against the caller, indexed by `call_index`
+    wins: Vec<Vec<f64>>,
+    losses: Vec<Vec<f64>>,
+}
+
+/// Values of the seats in the middle of the hand, for the current average charts
+struct PushFoldPlan {
+    /// Values once the seats before the index folded, the index of the big blind being the walk
+    opens: Vec<Vec<f64>>,
+    /// Values once a seat jammed and the seats before the second index folded to it
+    tails: Vec<Vec<Vec<f64>>>,
+}
+
+impl PushFoldSolver {
+    fn new(spot: &PushFoldSpot, table: &PreflopEquityTable) -> Self {
+        let n = spot.stacks.len();
+        let mut equity = Vec::with_capacity(HandClass::COUNT * HandClass::COUNT);
+        let mut combos = Vec::with_capacity(HandClass::COUNT * HandClass::COUNT);
+        for class in HandClass::all() {
+            for other in HandClass::all() {
+                equity.push(table.equity(class, other));
+                combos.push(table.combos(class, other) as f64);
+            }
+        }
+        let outcome =
+            |jammer, caller, winner| spot.utilities(&spot.final_stacks(jammer, caller, winner));
+        let mut wins = vec![Vec::new(); n * n];
+        let mut losses = vec![Vec::new(); n * n];
+        for jammer in 0..n {
+            for caller in jammer + 1..n {
+                wins[call_index(n, jammer, caller)] = outcome(jammer, Some(caller), jammer);
+                losses[call_index(n, jammer, caller)] = outcome(jammer, Some(caller), caller);
+            }
+        }
+        PushFoldSolver {
+            n,
+            equity,
+            combos,
+            walk: outcome(n - 1, None, n - 1),
+            steals: (0..n).map(|jammer| outcome(jammer, None, jammer)).collect(),
+            wins,
+            losses,
+        }
+    }
+
+    /// Chance of `caller` calling and equity of the jammer, given the weight of every class in
+    /// the jamming range
+    fn showdown(&self, jammer_range: &[f64], calls: &[f64]) -> (f64, f64) {
+        let k = HandClass::COUNT;
+        let (mut dealt, mut called, mut won) = (0.0, 0.0, 0.0);
+        for (class, &weight) in jammer_range.iter().enumerate().filter(|(_, w)| **w > 0.0) {
+            let row = class * k..(class + 1) * k;
+            for ((&combos, &equity), &call) in self.combos[row.clone()]
+                .iter()
+                .zip(&self.equity[row])
+                .zip(calls)
+            {
+                dealt += weight * combos;
+                called += weight * combos * call;
+                won += weight * combos * call * equity;
+            }
+        }
+        match called > 0.0 {
+            true => (called / dealt, won / called),
+            false => (0.0, 0.5),
+        }
+    }
+
+    fn plan(&self, jams: &[Vec<f64>], calls: &[Vec<f64>]) -> PushFoldPlan {
+        let n = self.n;
+        let dealt = |class: usize| HandClass::from_index(class).combos() as f64 / 1326.0;
+        let mut showdowns = vec![(0.0, 0.5); n * n];
+        let mut tails = vec![Vec::new(); n];
+        for jammer in 0..n - 1 {
+            for caller in jammer + 1..n {
+                let index = call_index(n, jammer, caller);
+                showdowns[index] = self.showdown(&jams[jammer], &calls[index]);
+            }
+            let mut tail = vec![self.steals[jammer].clone()];
+            for caller in (jammer + 1..n).rev() {
+                let index = call_index(n, jammer, caller);
+                let (called, won) = showdowns[index];
+                let next = tail.last().unwrap();
+                let value = (0..n)
+                    .map(|seat| {
+                        called
+                            * (won * self.wins[index][seat]
+                                + (1.0 - won) * self.losses[index][seat])
+                            + (1.0 - called) * next[seat]
+                    })
+                    .collect();
+                tail.push(value);
+            }
+            // indexed by the first seat left to respond
+            tail.extend(vec![Vec::new(); jammer + 1]);
+            tail.reverse();
+            tails[jammer] = tail;
+        }
+
+        let mut opens = vec![self.walk.clone()];
+        for jammer in (0..n - 1).rev() {
+            let jammed: f64 = jams[jammer]
+                .iter()
+                .enumerate()
+                .map(|(class, frequency)| dealt(class) * frequency)
+                .sum();
+            let next = opens.last().unwrap();
+            let value = (0..n)
+                .map(|seat| jammed * tails[jammer][jammer + 1][seat] + (1.0 - jammed) * next[seat])
+                .collect();
+            opens.push(value);
+        }
+        opens.reverse();
+        PushFoldPlan { opens, tails }
+    }
+
+    /// Value for `jammer` of jamming `class`, the seats after it calling with their charts
+    fn jam_value(&self, calls: &[Vec<f64>], jammer: usize, class: usize) -> f64 {
+        let n = self.n;
+        let mut range = vec![0.0; HandClass::COUNT];
+        range[class] = 1.0;
+        let mut reached = 1.0;
+        let mut value = 0.0;
+        for caller in jammer + 1..n {
+            let index = call_index(n, jammer, caller);
+            let (called, won) = self.showdown(&range, &calls[index]);
+            value += reached
+                * called
+                * (won * self.wins[index][jammer] + (1.0 - won) * self.losses[index][jammer]);
+            reached *= 1.0 - called;
+        }
+        value + reached * self.steals[jammer][jammer]
+    }
+
+    /// Value for `caller` of calling the jam of `jammer` with `class`
+    fn call_value(&self, jam: &[f64], jammer: usize, caller: usize, class: usize) -> f64 {
+        let index = call_index(self.n, jammer, caller);
+        let k = HandClass::COUNT;
+        let (mut dealt, mut won) = (0.0, 0.0);
+        for (jammed, &frequency) in jam.iter().enumerate() {
+            let combos = frequency * self.combos[jammed * k + class];
+            dealt += combos;
+            won += combos * self.equity[jammed * k + class];
+        }
+        // any jam when the chart never jams
+        let won = match dealt > 0.0 {
+            true => won / dealt,
+            false => 1.0 - self.equity[class * k..(class + 1) * k].iter().sum::<f64>() / k as f64,
+        };
+        won * self.wins[index][caller] + (1.0 - won) * self.losses[index][caller]
+    }
+}
+
+#[test]
+fn push_fold_heads_up() {
+    use rand::SeedableRng;
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+    let table = PreflopEquityTable::estimate(20, &mut rng);
+    let aces = HandClass::from_index(0);
+    let seven_deuce = HandClass::all()
+        .find(|class| class.to_string() == "72o")
+        .unwrap();
+    assert!(table.equity(aces, seven_deuce) > 0.8);
+    assert_eq!(table.combos(aces, aces), 6);
+
+    let spot = |big_blinds: usize| PushFoldSpot {
+        stacks: vec![Chips::new(big_blinds * 10); 2],
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        ante: Chips::ZERO,
+        payouts: None,
+    };
+    let solution = solve_push_fold(&spot(10), &table, 200).unwrap();
+    let jams = solution.jam_chart(0).unwrap();
+    let calls = solution.call_chart(0, 1).unwrap();
+    assert!(solution.jam_chart(1).is_none());
+    assert!(jams.plays(aces) && calls.plays(aces));
+    assert!(!calls.plays(seven_deuce));
+    // at ten big blinds the small blind jams about 60% of its hands, and the big blind calls
+    // with about a third of them
+    assert!((0.45..0.75).contains(&jams.range_size()));
+    assert!((0.25..0.5).contains(&calls.range_size()));
+    assert!((solution.values().iter().sum::<f64>() - 200.0).abs() < 1e-6);
+    assert_eq!(jams.grid()[0][0], jams.frequency(aces));
+    assert!(jams.to_string().starts_with("AA  AKs"));
+
+    // shorter stacks jam wider
+    let short = solve_push_fold(&spot(3), &table, 200).unwrap();
+    assert!(short.jam_chart(0).unwrap().range_size() > jams.range_size());
+
+    // three-handed with antes, valued in prize equity: the button jams tighter than the small
+    // blind and every seat gets a share of the prizes
+    let spot = PushFoldSpot {
+        stacks: vec![Chips::new(100); 3],
+        ante: Chips::new(1),
+        payouts: Some(vec![0.65, 0.35]),
+        ..spot(10)
+    };
+    let three_handed = solve_push_fold(&spot, &table, 100).unwrap();
+    let button = three_handed.jam_chart(0).unwrap();
+    assert!(button.plays(aces));
+    assert!(button.range_size() < three_handed.jam_chart(1).unwrap().range_size());
+    assert!(three_handed.call_chart(0, 2).unwrap().plays(aces));
+    assert!((three_handed.values().iter().sum::<f64>() - 1.0).abs() < 1e-9);
+
+    // a stack shorter than the big blind only wins what it matches, the rest of the blind
+    // going back
+    let short_stacked = PushFoldSpot {
+        stacks: vec![Chips::new(3), Chips::new(100)],
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        ante: Chips::ZERO,
+        payouts: None,
+    };
+    assert_eq!(
+        short_stacked.final_stacks(0, Some(1), 0),
+        vec![Chips::new(6), Chips::new(97)]
+    );
+    assert_eq!(
+        short_stacked.final_stacks(0, Some(1), 1),
+        vec![Chips::ZERO, Chips::new(103)]
+    );
+    let solution = solve_push_fold(&short_stacked, &table, 20).unwrap();
+    assert!((solution.values().iter().sum::<f64>() - 103.0).abs() < 1e-6);
+    let inverted = PushFoldSpot {
+        small_blind: Chips::new(20),
+        ..short_stacked
+    };
+    assert!(solve_push_fold(&inverted, &table, 20).is_err());
+
+    // every seat folds or jams all-in, and the big blind folds or calls
+    let settings = Settings {
+        n_players: 2,
+        initial_stack: Chips::new(100),
+        small_blind: Chips::new(5),
+        big_blind: Chips::new(10),
+        ..Default::default()
+    };
+    let mut agents: Vec<Box<dyn Agent>> = vec![
+        Box::new(PolicyAgent::new(solution.clone(), 1)),
+        Box::new(PolicyAgent::new(solution, 2)),
+    ];
+    for seed in 0..20 {
+        let mut game = Game::with_seed(settings.clone(), seed).unwrap();
+        let result = play_hand(&mut game, &mut agents).unwrap();
+        let lost = result.deltas.iter().map(|delta| delta.abs()).max().unwrap();
+        match result.showdown {
+            true => assert!(lost == 100 || lost == 0),
+            false => assert!(lost <= 10),
+        }
+    }
+}
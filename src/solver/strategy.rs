@@ -0,0 +1,667 @@
+use crate::solver::*;
+use memmap2::Mmap;
+use std::{fs::File, io::Write, path::Path};
+
+// Layout of a strategy file, all numbers little-endian:
+//
+// header (64 bytes)
+//    0  magic             [u8; 8]
+//    8  version           u32
+//   12  n_buckets         u32
+//   16  n_nodes           u32
+//   20  n_edges           u32
+//   24  metadata length   u32
+//   28  metadata crc32    u32
+//   32  tree offset       u64
+//   40  strategy offset   u64
+//   48  strategy length   u64
+//   56  data crc32        u32, of the tree and the strategy
+//   60  header crc32      u32, of the 60 bytes before
+// metadata
+//   initial stack, small blind, big blind           u64 each
+//   max raises u32, all-in u8, fraction count u32, pot fractions f64 each
+//   bucket of every hand class                      u16 each
+//   joint bucket probabilities, bucket equities     f64 each, row-major
+// tree
+//   nodes (16 bytes): kind u8, player u8, round u8, action count u8, first edge u32,
+//                     offset of the first probability u64
+//   edges (16 bytes): action u8, padding [u8; 3], child u32, pot fraction f64
+// strategy
+//   action probabilities f32 each, bucket-major for every decision node
+
+const MAGIC: &[u8; 8] = b"POKRSTRT";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 64;
+const NODE_LEN: usize = 16;
+const EDGE_LEN: usize = 16;
+
+/// Average strategy of a solved `AbstractHoldem`, read from a memory-mapped file
+///
+/// Only the header, the abstraction metadata, the tree and the pages holding the looked up
+/// probabilities are brought into memory.
+pub struct Strategy {
+    data: Mmap,
+    settings: Settings,
+    bets: BetAbstraction,
+    cards: CardAbstraction,
+    n_nodes: usize,
+    tree_offset: usize,
+    strategy_offset: usize,
+    data_crc: u32,
+}
+
+impl Strategy {
+    pub fn save(
+        path: impl AsRef<Path>,
+        game: &AbstractHoldem,
+        profile: &StrategyProfile,
+    ) -> Result<(), StrategyError> {
+        let settings = game.settings();
+        let bets = game.bets();
+        let cards = game.cards();
+        let n_buckets = cards.n_buckets();
+
+        let mut metadata = Vec::new();
+        for value in [
+            settings.initial_stack,
+            settings.small_blind,
+            settings.big_blind,
+        ] {
+            metadata.extend((value.get() as u64).to_le_bytes());
+        }
+        metadata.extend((bets.max_raises as u32).to_le_bytes());
+        metadata.push(bets.all_in as u8);
+        metadata.extend((bets.pot_fractions.len() as u32).to_le_bytes());
+        for fraction in &bets.pot_fractions {
+            metadata.extend(fraction.to_le_bytes());
+        }
+        for &bucket in cards.class_buckets() {
+            metadata.extend((bucket as u16).to_le_bytes());
+        }
+        for b0 in 0..n_buckets {
+            for b1 in 0..n_buckets {
+                metadata.extend(cards.joint_probability(0, b0, b1).to_le_bytes());
+            }
+        }
+        for b0 in 0..n_buckets {
+            for b1 in 0..n_buckets {
+                metadata.extend(cards.equity(0, b0, b1).to_le_bytes());
+            }
+        }
+
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        let mut strategy = Vec::new();
+        let mut n_edges = 0u32;
+        for (id, node) in game.nodes().iter().enumerate() {
+            let (kind, player, round, actions): (u8, usize, Round, &[(AbstractAction, usize)]) =
+                match node {
+                    Node::Decision {
+                        player,
+                        round,
+                        actions,
+                    } => (0, *player, *round, actions),
+                    Node::Fold { player, .. } => (1, *player, Round::River, &[]),
+                    Node::Showdown { .. } => (2, 0, Round::River, &[]),
+                };
+            nodes.push(kind);
+            nodes.push(player as u8);
+            nodes.push(round_code(round));
+            nodes.push(actions.len() as u8);
+            nodes.extend(n_edges.to_le_bytes());
+            nodes.extend(((strategy.len() / 4) as u64).to_le_bytes());
+
+            for &(action, child) in actions {
+                let (code, fraction) = action_code(action);
+                edges.push(code);
+                edges.extend([0; 3]);
+                edges.extend((child as u32).to_le_bytes());
+                edges.extend(fraction.to_le_bytes());
+                n_edges += 1;
+            }
+            if !actions.is_empty() {
+                for bucket in 0..n_buckets {
+                    for &p in profile.action_probabilities(id, bucket) {
+                        strategy.extend((p as f32).to_le_bytes());
+                    }
+                }
+            }
+        }
+
+        let tree_offset = HEADER_LEN + metadata.len();
+        let strategy_offset = tree_offset + nodes.len() + edges.len();
+
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend(MAGIC);
+        header.extend(VERSION.to_le_bytes());
+        header.extend((n_buckets as u32).to_le_bytes());
+        header.extend((game.nodes().len() as u32).to_le_bytes());
+        header.extend(n_edges.to_le_bytes());
+        header.extend((metadata.len() as u32).to_le_bytes());
+        header.extend(crc32fast::hash(&metadata).to_le_bytes());
+        header.extend((tree_offset as u64).to_le_bytes());
+        header.extend((strategy_offset as u64).to_le_bytes());
+        header.extend((strategy.len() as u64).to_le_bytes());
+        let mut data_crc = crc32fast::Hasher::new();
+        for part in [&nodes, &edges, &strategy] {
+            data_crc.update(part);
+        }
+        header.extend(data_crc.finalize().to_le_bytes());
+        header.extend(crc32fast::hash(&header).to_le_bytes());
+
+        let mut file = File::create(path)?;
+        for part in [&header, &metadata, &nodes, &edges, &strategy] {
+            file.write_all(part)?;
+        }
+        Ok(())
+    }
+
+    /// Maps a strategy file, checking its header, its metadata and the shape of its tree but not
+    /// the probabilities
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StrategyError> {
+        let file = File::open(path)?;
+        // safety: strategy files are written once and not modified while in use
+        let data = unsafe { Mmap::map(&file)? };
+
+        if data.len() < HEADER_LEN || &data[..8] != MAGIC {
+            return Err(StrategyError::InvalidFormat);
+        }
+        if read_u32(&data, 60) != crc32fast::hash(&data[..60]) {
+            return Err(StrategyError::ChecksumMismatch);
+        }
+        let version = read_u32(&data, 8);
+        if version != VERSION {
+            return Err(StrategyError::UnsupportedVersion(version));
+        }
+
+        let n_buckets = read_u32(&data, 12) as usize;
+        let n_nodes = read_u32(&data, 16) as usize;
+        let n_edges = read_u32(&data, 20) as usize;
+        let metadata_len = read_u32(&data, 24) as usize;
+        let tree_offset = read_u64(&data, 32) as usize;
+        let strategy_offset = read_u64(&data, 40) as usize;
+        let strategy_len = read_u64(&data, 48) as usize;
+        if tree_offset != HEADER_LEN + metadata_len
+            || strategy_offset != tree_offset + n_nodes * NODE_LEN + n_edges * EDGE_LEN
+            || strategy_offset.checked_add(strategy_len) != Some(data.len())
+        {
+            return Err(StrategyError::InvalidFormat);
+        }
+
+        let metadata = &data[HEADER_LEN..tree_offset];
+        if read_u32(&data, 28) != crc32fast::hash(metadata) {
+            return Err(StrategyError::ChecksumMismatch);
+        }
+
+        let mut reader = Reader {
+            bytes: metadata,
+            offset: 0,
+        };
+        let settings = Settings {
+            n_players: 2,
+            initial_stack: reader.chips()?,
+            small_blind: reader.chips()?,
+            big_blind: reader.chips()?,
+            ..Default::default()
+        };
+        let max_raises = reader.u32()? as usize;
+        let all_in = reader.u8()? != 0;
+        let n_fractions = reader.u32()? as usize;
+        let pot_fractions = (0..n_fractions)
+            .map(|_| reader.f64())
+            .collect::<Result<_, _>>()?;
+        let class_buckets: Vec<usize> = (0..HandClass::COUNT)
+            .map(|_| reader.u16().map(|b| b as usize))
+            .collect::<Result<_, _>>()?;
+        if class_buckets.iter().any(|&bucket| bucket >= n_buckets) {
+            return Err(StrategyError::InvalidFormat);
+        }
+        let joint = (0..n_buckets * n_buckets)
+            .map(|_| reader.f64())
+            .collect::<Result<_, _>>()?;
+        let equity = (0..n_buckets * n_buckets)
+            .map(|_| reader.f64())
+            .collect::<Result<_, _>>()?;
+        check_tree(
+            &data[tree_offset..strategy_offset],
+            n_nodes,
+            n_buckets,
+            strategy_len / 4,
+        )?;
+
+        Ok(Strategy {
+            settings,
+            bets: BetAbstraction {
+                pot_fractions,
+                max_raises,
+                all_in,
+            },
+            cards: CardAbstraction::new(class_buckets, joint, equity),
+            n_nodes,
+            tree_offset,
+            strategy_offset,
+            data_crc: read_u32(&data, 56),
+            data,
+        })
+    }
+
+    /// Checks the tree and the probabilities against their checksum, reading the whole file
+    pub fn verify(&self) -> Result<(), StrategyError> {
+        if crc32fast::hash(&self.data[self.tree_offset..]) == self.data_crc {
+            Ok(())
+        } else {
+            Err(StrategyError::ChecksumMismatch)
+        }
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn bets(&self) -> &BetAbstraction {
+        &self.bets
+    }
+
+    pub fn cards(&self) -> &CardAbstraction {
+        &self.cards
+    }
+
+    /// Probabilities of the actions of a node of the abstract game for a bucket, empty for
+    /// terminal nodes, `OffTree` for a node or a bucket the file does not have
+    pub fn node_probabilities(
+        &self,
+        node: usize,
+        bucket: usize,
+    ) -> Result<Vec<f64>, StrategyError> {
+        if node >= self.n_nodes || bucket >= self.cards.n_buckets() {
+            return Err(StrategyError::OffTree);
+        }
+        let n_actions = self.data[self.node_offset(node) + 3] as usize;
+        let first = read_u64(&self.data, self.node_offset(node) + 8) as usize;
+        let offset = self.strategy_offset + (first + bucket * n_actions) * 4;
+        Ok((0..n_actions)
+            .map(|a| read_f32(&self.data, offset + a * 4) as f64)
+            .collect())
+    }
+
+    /// Probabilities of the real actions the blueprint takes in a live heads-up game
+    ///
+    /// The actions of the hand are translated onto the abstract game to find the node to play
+    /// from, and its abstract actions are sized for the live pot and stacks. Hands with more
+    /// than two seats, or with other blinds, starting stacks or betting than the blueprint's
+    /// settings, are `OffTree`.
+    pub fn action_probabilities(
+        &self,
+        state: &ObservableState,
+    ) -> Result<Vec<(Action, f64)>, StrategyError> {
+        let hand = state.hand.ok_or(StrategyError::OffTree)?;
+        let seats = self.blind_seats(state)?;
+        let player = |seat: usize| usize::from(seat != seats[0]);
+
+        let mut betting = LiveBetting::new(state, seats);
+        let mut node = 0;
+        for record in &state.history {
+            betting.start_round(record.round, state.big_blind);
+            let situation = betting.situation(player(record.seat));
+            let translated = self
+                .bets
+                .translate(record.action, &situation)
+                .into_iter()
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(action, _)| action)
+                .ok_or(StrategyError::OffTree)?;
+            node = self.child(node, translated).ok_or(StrategyError::OffTree)?;
+            betting.play(player(record.seat), record.action, &situation);
+        }
+
+        if self.data[self.node_offset(node)] != 0
+            || self.data[self.node_offset(node) + 1] as usize != player(state.current_seat)
+        {
+            return Err(StrategyError::OffTree);
+        }
+
+        let situation = BetSituation::from(state);
+        let mut probabilities: Vec<(Action, f64)> = Vec::new();
+        let node_probabilities = self.node_probabilities(node, self.cards.bucket(&hand))?;
+        for (edge, p) in self.edges(node).zip(node_probabilities) {
+            let action = self.bets.to_action(edge.0, &situation);
+            match probabilities.iter_mut().find(|(a, _)| *a == action) {
+                Some((_, total)) => *total += p,
+                None => probabilities.push((action, p)),
+            }
+        }
+        Ok(probabilities)
+    }
+
+    /// Small and big blind seats of a live hand played like the blueprint
+    fn blind_seats(&self, state: &ObservableState) -> Result<[usize; 2], StrategyError> {
+        let live: Vec<usize> = (0..state.seats.len())
+            .filter(|&seat| !state.seats[seat].is_dead)
+            .collect();
+        let [first, second] = live[..] else {
+            return Err(StrategyError::OffTree);
+        };
+        let bb_seat = if first == state.sb_seat {
+            second
+        } else {
+            first
+        };
+        let settings = &self.settings;
+        if !live.contains(&state.sb_seat)
+            || state.small_blind != settings.small_blind
+            || state.big_blind != settings.big_blind
+            || state.betting != settings.betting
+            || [state.sb_seat, bb_seat]
+                .iter()
+                .any(|&seat| state.starting_stacks[seat] != settings.initial_stack)
+        {
+            return Err(StrategyError::OffTree);
+        }
+        Ok([state.sb_seat, bb_seat])
+    }
+
+    fn node_offset(&self, node: usize) -> usize {
+        self.tree_offset + node * NODE_LEN
+    }
+
+    fn edges(&self, node: usize) -> impl Iterator<Item = (AbstractAction, usize)> + '_ {
+        let n_actions = self.data[self.node_offset(node) + 3] as usize;
+        let first = read_u32(&self.data, self.node_offset(node) + 4) as usize;
+        let edges_offset = self.tree_offset + self.n_nodes * NODE_LEN;
+        (first..first + n_actions).map(move |edge| {
+            let offset = edges_offset + edge * EDGE_LEN;
+            let action = match self.data[offset] {
+                0 => AbstractAction::Fold,
+                1 => AbstractAction::Call,
+                2 => AbstractAction::PotRaise(read_f64(&self.data, offset + 8)),
+                _ => AbstractAction::AllIn,
+            };
+            (action, read_u32(&self.data, offset + 4) as usize)
+        })
+    }
+
+    fn child(&self, node: usize, action: AbstractAction) -> Option<usize> {
+        self.edges(node)
+            .find(|(a, _)| *a == action)
+            .map(|(_, child)| child)
+    }
+}
+
+/// Falls back to checking or calling off the abstract tree
+impl Policy for Strategy {
+    fn action_probabilities(&self, state: &ObservableState) -> Vec<(Action, f64)> {
+        Strategy::action_probabilities(self, state).unwrap_or_else(|_| {
+            if state.legal_actions().can_check() {
+                vec![(Action::Check, 1.0)]
+            } else {
+                vec![(Action::Call, 1.0)]
+            }
+        })
+    }
+}
+
+/// Heads-up bets and stacks replayed from the history of a live hand
+struct LiveBetting {
+    stacks: [Chips; 2],
+    contributions: [Chips; 2],
+    bets: [Chips; 2],
+    last_raise: Chips,
+    raises: usize,
+    round: Round,
+}
+
+impl LiveBetting {
+    /// Betting of the small and big blind `seats` before anyone acts
+    fn new(state: &ObservableState, seats: [usize; 2]) -> Self {
+        let stacks = seats.map(|seat| state.starting_stacks[seat]);
+        let blinds = [
+            state.small_blind.min(stacks[0]),
+            state.big_blind.min(stacks[1]),
+        ];
+        LiveBetting {
+            stacks: [stacks[0] - blinds[0], stacks[1] - blinds[1]],
+            contributions: blinds,
+            bets: blinds,
+            last_raise: state.big_blind,
+            raises: 0,
+            round: Round::PreFlop,
+        }
+    }
+
+    fn start_round(&mut self, round: Round, big_blind: Chips) {
+        if round != self.round {
+            self.round = round;
+            self.bets = [Chips::ZERO; 2];
+            self.last_raise = big_blind;
+            self.raises = 0;
+        }
+    }
+
+    fn situation(&self, player: usize) -> BetSituation {
+        let to_call = self.bets[1 - player].saturating_sub(self.bets[player]);
+        let stack = self.stacks[player];
+        BetSituation {
+            pot: self.contributions[0] + self.contributions[1],
+            call_amount: to_call.min(stack),
+            raise_range: (stack > to_call && !self.stacks[1 - player].is_zero()).then(|| {
+                let max = stack - to_call;
+                (self.last_raise.max(Chips::new(1)).min(max), max)
+            }),
+            raises: self.raises,
+        }
+    }
+
+    fn play(&mut self, player: usize, action: Action, situation: &BetSituation) {
+        let amount = match action {
+            Action::Fold | Action::Check => Chips::ZERO,
+            Action::Call => situation.call_amount,
+            Action::Raise(raise) => {
+                self.last_raise = self.last_raise.max(raise);
+                self.raises += 1;
+                situation.call_amount + raise
+            }
+        };
+        self.stacks[player] -= amount;
+        self.contributions[player] += amount;
+        self.bets[player] += amount;
+    }
+}
+
+fn round_code(round: Round) -> u8 {
+    match round {
+        Round::PreFlop => 0,
+        Round::Flop => 1,
+        Round::Turn => 2,
+        Round::River => 3,
+    }
+}
+
+fn action_code(action: AbstractAction) -> (u8, f64) {
+    match action {
+        AbstractAction::Fold => (0, 0.0),
+        AbstractAction::Call => (1, 0.0),
+        AbstractAction::PotRaise(fraction) => (2, fraction),
+        AbstractAction::AllIn => (3, 0.0),
+    }
+}
+
+/// Checks that every node of a tree points to edges, children and probabilities the file has,
+/// for lookups to stay within the file
+fn check_tree(
+    tree: &[u8],
+    n_nodes: usize,
+    n_buckets: usize,
+    n_probabilities: usize,
+) -> Result<(), StrategyError> {
+    let edges = &tree[n_nodes * NODE_LEN..];
+    let n_edges = edges.len() / EDGE_LEN;
+    let valid = n_nodes > 0
+        && (0..n_nodes).all(|node| {
+            let offset = node * NODE_LEN;
+            let (kind, player, round) = (tree[offset], tree[offset + 1], tree[offset + 2]);
+            let n_actions = tree[offset + 3] as usize;
+            let first_edge = read_u32(tree, offset + 4) as usize;
+            let first_probability = read_u64(tree, offset + 8) as usize;
+            let probabilities = n_buckets
+                .checked_mul(n_actions)
+                .and_then(|n| n.checked_add(first_probability));
+            kind <= 2
+                && player <= 1
+                && round <= 3
+                && (kind == 0) == (n_actions > 0)
+                && first_edge + n_actions <= n_edges
+                && probabilities.is_some_and(|end| end <= n_probabilities)
+                && (first_edge..first_edge + n_actions).all(|edge| {
+                    let offset = edge * EDGE_LEN;
+                    edges[offset] <= 3 && (read_u32(edges, offset + 4) as usize) < n_nodes
+                })
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(StrategyError::InvalidFormat)
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_f32(data: &[u8], offset: usize) -> f32 {
+    f32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_f64(data: &[u8], offset: usize) -> f64 {
+    f64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], StrategyError> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset + N)
+            .ok_or(StrategyError::InvalidFormat)?;
+        self.offset += N;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, StrategyError> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, StrategyError> {
+        Ok(u16::from_le_bytes(self.take()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, StrategyError> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, StrategyError> {
+        Ok(u64::from_le_bytes(self.take()?))
+    }
+
+    fn f64(&mut self) -> Result<f64, StrategyError> {
+        Ok(f64::from_le_bytes(self.take()?))
+    }
+
+    fn chips(&mut self) -> Result<Chips, StrategyError> {
+        Ok(Chips::new(self.u64()? as usize))
+    }
+}
+
+#[test]
+fn strategy_file_round_trip() {
+    let settings = Settings {
+        n_players: 2,
+        initial_stack: Chips::new(200),
+        small_blind: Chips::new(1),
+        big_blind: Chips::new(2),
+        ..Default::default()
+    };
+    let cards = CardAbstraction::new(
+        (0..HandClass::COUNT).map(|i| i % 2).collect(),
+        vec![0.25; 4],
+        vec![0.5, 0.3, 0.7, 0.5],
+    );
+    let game = AbstractHoldem::new(&settings, cards, &BetAbstraction::from(&settings));
+    let mut profile = StrategyProfile::uniform(&game);
+    let n_root_actions = profile.action_probabilities(game.root(), 1).len();
+    let mut root = vec![0.0; n_root_actions];
+    root[1] = 0.25;
+    root[n_root_actions - 1] = 0.75;
+    profile.set_action_probabilities(game.root(), 1, &root);
+
+    let path = std::env::temp_dir().join(format!("pokr-strategy-{}.bin", std::process::id()));
+    Strategy::save(&path, &game, &profile).unwrap();
+    let strategy = Strategy::open(&path).unwrap();
+    strategy.verify().unwrap();
+    assert_eq!(strategy.bets(), game.bets());
+    assert_eq!(strategy.node_probabilities(game.root(), 1).unwrap(), root);
+    assert!(matches!(
+        strategy.node_probabilities(game.root(), 2),
+        Err(StrategyError::OffTree)
+    ));
+
+    // the blueprint answers live states, including after an off-tree raise
+    let mut live = Game::with_seed(settings.clone(), 0).unwrap();
+    let state = live.get_observable_state(live.current_seat());
+    let probabilities = strategy.action_probabilities(&state).unwrap();
+    assert!((probabilities.iter().map(|(_, p)| p).sum::<f64>() - 1.0).abs() < 1e-6);
+    live.play_turn(Action::Raise(Chips::new(7))).unwrap();
+    let state = live.get_observable_state(live.current_seat());
+    assert!(strategy.action_probabilities(&state).is_ok());
+
+    // but not the hands of other tables
+    for other in [
+        Settings {
+            n_players: 3,
+            ..settings.clone()
+        },
+        Settings {
+            big_blind: Chips::new(4),
+            ..settings.clone()
+        },
+        Settings {
+            initial_stack: Chips::new(100),
+            ..settings.clone()
+        },
+    ] {
+        let live = Game::with_seed(other, 0).unwrap();
+        let state = live.get_observable_state(live.current_seat());
+        assert!(matches!(
+            strategy.action_probabilities(&state),
+            Err(StrategyError::OffTree)
+        ));
+    }
+
+    // corrupted metadata is refused
+    let bytes = std::fs::read(&path).unwrap();
+    let mut corrupted = bytes.clone();
+    corrupted[HEADER_LEN] ^= 1;
+    std::fs::write(&path, &corrupted).unwrap();
+    assert!(matches!(
+        Strategy::open(&path),
+        Err(StrategyError::ChecksumMismatch)
+    ));
+
+    // so is a tree pointing outside of the file, even without checking the probabilities
+    let mut corrupted = bytes;
+    let first_edge = strategy.tree_offset + strategy.n_nodes * NODE_LEN;
+    corrupted[first_edge + 4..first_edge + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+    std::fs::write(&path, &corrupted).unwrap();
+    assert!(matches!(
+        Strategy::open(&path),
+        Err(StrategyError::InvalidFormat)
+    ));
+    std::fs::remove_file(&path).unwrap();
+}
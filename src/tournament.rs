@@ -0,0 +1,299 @@
+use crate::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Blinds of a tournament, raised every `hands_per_level` hands
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlindSchedule {
+    /// Small and big blind of every level, the last level lasting until the end
+    pub levels: Vec<(Chips, Chips)>,
+    pub hands_per_level: usize,
+}
+
+impl BlindSchedule {
+    /// Level of the hand numbered `hand` from zero
+    pub fn level(&self, hand: usize) -> usize {
+        (hand / self.hands_per_level.max(1)).min(self.levels.len().saturating_sub(1))
+    }
+
+    pub fn blinds(&self, hand: usize) -> (Chips, Chips) {
+        self.levels[self.level(hand)]
+    }
+}
+
+/// Single table tournament played until one seat holds all the chips
+///
+/// Every hand is dealt with the blinds of the current level, seats out of chips being skipped.
+/// The small blind moves to the next seat that still has chips, and the big blind follows it.
+/// Seats busted in the same hand finish in the order of their stacks at the start of the hand,
+/// equal stacks sharing the best of their positions.
+#[derive(Clone, Debug)]
+pub struct Tournament {
+    /// Blinds of the settings are replaced by the ones of the schedule
+    settings: Settings,
+    schedule: BlindSchedule,
+    game: Game,
+    /// Hands dealt before the current one
+    hands: usize,
+    /// Stacks at the start of the current hand, before the blinds
+    stacks: Vec<Chips>,
+    sb_seat: usize,
+    /// Finishing position of every busted seat, 1 being the winner
+    positions: Vec<Option<usize>>,
+    rng: StdRng,
+}
+
+impl Tournament {
+    /// Tournament of `settings`, which must not take any rake since the chips never leave play
+    pub fn new(settings: Settings, schedule: BlindSchedule, seed: u64) -> Result<Self, GameError> {
+        let valid_level = |&(small_blind, big_blind)| {
+            Settings {
+                small_blind,
+                big_blind,
+                ..settings.clone()
+            }
+            .validate()
+            .is_ok()
+        };
+        if schedule.levels.is_empty()
+            || !schedule.levels.iter().all(valid_level)
+            || settings.rake.basis_points > 0
+        {
+            return Err(GameError::InvalidSettings);
+        }
+        let n = settings.n_players;
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (small_blind, big_blind) = schedule.levels[0];
+        // replaced by the first hand right away
+        let game = Game::with_seed(
+            Settings {
+                small_blind,
+                big_blind,
+                ..settings.clone()
+            },
+            rng.random(),
+        )?;
+        let mut tournament = Tournament {
+            stacks: vec![settings.initial_stack; n],
+            settings,
+            schedule,
+            game,
+            hands: 0,
+            sb_seat: 0,
+            positions: vec![None; n],
+            rng,
+        };
+        tournament.deal()?;
+        Ok(tournament)
+    }
+
+    /// Game of the current hand
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Plays an action in the current hand, dealing the next one once it is over
+    pub fn play_turn(&mut self, action: Action) -> Result<(), GameError> {
+        if self.over() {
+            return Err(GameError::GameOver);
+        }
+        self.game.play_turn(action)?;
+        if self.game.hand_count() > 0 {
+            self.finish_hand()?;
+        }
+        Ok(())
+    }
+
+    /// Plays the current hand to its end with one agent per seat, then deals the next one
+    pub fn play_hand(&mut self, agents: &mut [Box<dyn Agent>]) -> Result<HandResult, GameError> {
+        if self.over() {
+            return Err(GameError::GameOver);
+        }
+        let result = play_hand(&mut self.game, agents)?;
+        self.finish_hand()?;
+        Ok(result)
+    }
+
+    /// Plays hands until the tournament is over and returns the finishing positions
+    pub fn play(&mut self, agents: &mut [Box<dyn Agent>]) -> Result<Vec<usize>, GameError> {
+        while !self.over() {
+            self.play_hand(agents)?;
+        }
+        Ok(self.positions.iter().flatten().copied().collect())
+    }
+
+    pub fn over(&self) -> bool {
+        self.stacks.iter().filter(|stack| !stack.is_zero()).count() < 2
+    }
+
+    /// Hands dealt so far, the current one included
+    pub fn hand_count(&self) -> usize {
+        self.hands + 1
+    }
+
+    /// Level of the current hand, from zero
+    pub fn level(&self) -> usize {
+        self.schedule.level(self.hands)
+    }
+
+    /// Small and big blind of the current hand
+    pub fn blinds(&self) -> (Chips, Chips) {
+        self.schedule.blinds(self.hands)
+    }
+
+    /// Stacks at the start of the current hand, or the final ones once the tournament is over
+    pub fn stacks(&self) -> &[Chips] {
+        &self.stacks
+    }
+
+    /// Position every seat finished in, `None` for the seats still playing
+    pub fn finishing_positions(&self) -> &[Option<usize>] {
+        &self.positions
+    }
+
+    /// Deals the current hand, finishing it right away when the blinds put everyone all-in
+    fn deal(&mut self) -> Result<(), GameError> {
+        let (small_blind, big_blind) = self.blinds();
+        let settings = Settings {
+            small_blind,
+            big_blind,
+            ..self.settings.clone()
+        };
+        let setup = HandSetup {
+            stacks: self.stacks.clone(),
+            sb_seat: self.sb_seat,
+            ..Default::default()
+        };
+        self.game = Game::from_setup(settings, &setup, self.rng.random())?;
+        if self.game.hand_count() > 0 {
+            self.finish_hand()?;
+        }
+        Ok(())
+    }
+
+    /// Records the seats the hand busted and deals the next hand unless the tournament is over
+    fn finish_hand(&mut self) -> Result<(), GameError> {
+        let result = self.game.last_hand_result().cloned().unwrap_or_default();
+        let stacks: Vec<Chips> = self
+            .stacks
+            .iter()
+            .zip(&result.deltas)
+            .map(|(&stack, &delta)| stack.saturating_add_signed(delta))
+            .collect();
+
+        let left = stacks.iter().filter(|stack| !stack.is_zero()).count();
+        let busted: Vec<usize> = (0..stacks.len())
+            .filter(|&seat| !self.stacks[seat].is_zero() && stacks[seat].is_zero())
+            .collect();
+        for &seat in &busted {
+            let ahead = busted
+                .iter()
+                .filter(|&&other| self.stacks[other] > self.stacks[seat])
+                .count();
+            self.positions[seat] = Some(left + ahead + 1);
+        }
+        self.stacks = stacks;
+        if left < 2 {
+            if let Some(winner) = self.stacks.iter().position(|stack| !stack.is_zero()) {
+                self.positions[winner] = Some(1);
+            }
+            return Ok(());
+        }
+
+        let n = self.stacks.len();
+        self.sb_seat = (1..=n)
+            .map(|i| (self.sb_seat + i) % n)
+            .find(|&seat| !self.stacks[seat].is_zero())
+            .unwrap_or(self.sb_seat);
+        self.hands += 1;
+        self.deal()
+    }
+}
+
+#[test]
+fn play_tournament() {
+    let settings = Settings {
+        n_players: 4,
+        initial_stack: Chips::new(200),
+        ..Default::default()
+    };
+    let schedule = BlindSchedule {
+        levels: [(5, 10), (10, 20), (25, 50), (50, 100)]
+            .into_iter()
+            .map(|(small_blind, big_blind)| (Chips::new(small_blind), Chips::new(big_blind)))
+            .collect(),
+        hands_per_level: 10,
+    };
+    let mut tournament = Tournament::new(settings.clone(), schedule.clone(), 3).unwrap();
+    assert_eq!(tournament.blinds(), (Chips::new(5), Chips::new(10)));
+    let mut agents: Vec<Box<dyn Agent>> = vec![
+        Box::new(RaisingAgent),
+        Box::new(CallingAgent),
+        Box::new(TightAggressiveAgent::new(1)),
+        Box::new(PotOddsAgent::new(2)),
+    ];
+
+    let mut last_level = 0;
+    while !tournament.over() {
+        assert!(tournament.level() >= last_level);
+        last_level = tournament.level();
+        let (small_blind, big_blind) = tournament.blinds();
+        let state = tournament.game().get_observable_state(0);
+        assert_eq!(
+            (state.small_blind, state.big_blind),
+            (small_blind, big_blind)
+        );
+        // busted seats are dealt out and never post a blind
+        assert!(!state.seats[state.sb_seat].is_dead);
+        for (seat, info) in state.seats.iter().enumerate() {
+            assert_eq!(
+                info.is_dead,
+                tournament.finishing_positions()[seat].is_some()
+            );
+        }
+        tournament.play_hand(&mut agents).unwrap();
+        assert_eq!(tournament.stacks().iter().sum::<Chips>(), Chips::new(800));
+    }
+
+    let mut positions = tournament.play(&mut agents).unwrap();
+    assert_eq!(positions.len(), 4);
+    assert_eq!(
+        positions.iter().filter(|&&position| position == 1).count(),
+        1
+    );
+    positions.sort();
+    assert!(
+        positions
+            .iter()
+            .zip(1..)
+            .all(|(&position, i)| position <= i)
+    );
+    let winner = tournament
+        .finishing_positions()
+        .iter()
+        .position(|&p| p == Some(1));
+    assert_eq!(tournament.stacks()[winner.unwrap()], Chips::new(800));
+    assert!(matches!(
+        tournament.play_hand(&mut agents),
+        Err(GameError::GameOver)
+    ));
+
+    let empty = BlindSchedule {
+        levels: Vec::new(),
+        ..schedule
+    };
+    assert!(Tournament::new(settings.clone(), empty, 0).is_err());
+
+    let raked = Settings {
+        rake: Rake {
+            basis_points: 500,
+            ..Default::default()
+        },
+        ..settings
+    };
+    assert!(matches!(
+        Tournament::new(raked, schedule, 0),
+        Err(GameError::InvalidSettings)
+    ));
+}
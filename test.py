@@ -1,6 +1,7 @@
 from pokr import PySettings
 from pokr import PyGame
 from pokr import PyAction
+from pokr import InvalidActionError
 
 settings = PySettings(3, 1000)
 game = PyGame(settings)
@@ -8,3 +9,18 @@ print(game.current_seat())
 action = PyAction.new_fold()
 game.play_turn(action)
 print(game.current_seat())
+
+try:
+    game.play_turn(PyAction.new_check())
+except InvalidActionError as error:
+    print(error)
+
+from pokr import PyPokerGymEnv
+
+env = PyPokerGymEnv(settings, ["calling", "tight_aggressive"])
+observation, info = env.reset(seed=0)
+terminated = False
+while not terminated:
+    mask = observation["action_mask"]
+    observation, reward, terminated, truncated, info = env.step(int(mask.argmax()))
+print(reward)